@@ -0,0 +1,119 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+// asciinema's v2 format, a JSON header then one event per line
+pub struct Cast {
+    out: BufWriter<File>,
+    delay: f64,
+    frames: usize,
+}
+
+impl Cast {
+    pub fn create(path: &Path, width: usize, height: usize, delay: f64) -> io::Result<Cast> {
+        let mut out = BufWriter::new(File::create(path)?);
+        writeln!(out, "{{\"version\": 2, \"width\": {}, \"height\": {}}}", width, height)?;
+
+        Ok(Cast { out, delay, frames: 0 })
+    }
+
+    // back to the top left corner and draw over the previous frame
+    pub fn frame(&mut self, lines: &[String]) -> io::Result<()> {
+        let screen = format!("\x1b[H{}", lines.join("\r\n"));
+        writeln!(
+            self.out,
+            "[{:.3}, \"o\", {}]",
+            self.frames as f64 * self.delay,
+            json_string(&screen)
+        )?;
+        self.frames += 1;
+
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+
+    out.push('"');
+    out
+}
+
+// An animated gif of a grid, each cell a scale x scale square coloured from
+// the palette.
+pub struct Gif {
+    encoder: gif::Encoder<BufWriter<File>>,
+    columns: usize,
+    scale: usize,
+    width: u16,
+    height: u16,
+    delay: u16,
+}
+
+impl Gif {
+    // the palette is red, green and blue for each colour in turn
+    pub fn create(path: &Path, columns: usize, rows: usize, scale: usize, delay: f64, palette: &[u8]) -> io::Result<Gif> {
+        let invalid = |e: String| io::Error::new(io::ErrorKind::InvalidInput, e);
+
+        let width = u16::try_from(columns * scale)
+            .map_err(|_| invalid(format!("{} pixels is too wide for a gif", columns * scale)))?;
+        let height = u16::try_from(rows * scale)
+            .map_err(|_| invalid(format!("{} pixels is too tall for a gif", rows * scale)))?;
+
+        let file = BufWriter::new(File::create(path)?);
+        let mut encoder = gif::Encoder::new(file, width, height, palette).map_err(io::Error::other)?;
+        encoder.set_repeat(gif::Repeat::Infinite).map_err(io::Error::other)?;
+
+        Ok(Gif {
+            encoder,
+            columns,
+            scale,
+            width,
+            height,
+            // gif delays are in hundredths of a second
+            delay: (delay * 100.0).round() as u16,
+        })
+    }
+
+    // the palette index of every cell, a row at a time
+    pub fn frame(&mut self, colours: &[u8]) -> io::Result<()> {
+        let mut pixels = Vec::with_capacity(self.width as usize * self.height as usize);
+        for row in colours.chunks(self.columns) {
+            let line = row
+                .iter()
+                .flat_map(|c| std::iter::repeat_n(*c, self.scale))
+                .collect::<Vec<u8>>();
+
+            for _ in 0..self.scale {
+                pixels.extend_from_slice(&line);
+            }
+        }
+
+        let mut frame = gif::Frame::from_indexed_pixels(self.width, self.height, pixels, None);
+        frame.delay = self.delay;
+
+        self.encoder.write_frame(&frame).map_err(io::Error::other)
+    }
+}
+
+#[test]
+fn test_json_string() {
+    assert_eq!("\"a\\\"b\\\\c\\r\\n\\u001b[H\"", json_string("a\"b\\c\r\n\x1b[H"));
+}
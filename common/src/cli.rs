@@ -0,0 +1,59 @@
+use std::{env, process, str::FromStr};
+
+// Every day's command line is `dayN [--input FILE] COMMAND [OPTION]...`,
+// without any arguments it prints the puzzle's answers.
+
+pub fn next_value<'a>(args: &mut impl Iterator<Item = &'a String>, flag: &str) -> Result<&'a String, String> {
+    args.next().ok_or(format!("{} needs a value", flag))
+}
+
+pub fn parse_number<T: FromStr>(value: &str) -> Result<T, String> {
+    value.parse::<T>().map_err(|_| format!("invalid number: {}", value))
+}
+
+// the input, the puzzle's unless --input says otherwise, and the command
+pub fn input_and_command<'a>(args: &mut impl Iterator<Item = &'a String>) -> Result<(&'a str, &'a String), String> {
+    let mut input = "input/problem.txt";
+
+    let mut command = args.next().ok_or("missing command")?;
+    if command == "--input" {
+        input = next_value(args, command)?;
+        command = args.next().ok_or("missing command")?;
+    }
+
+    Ok((input, command))
+}
+
+// Runs the command given on the command line, or answers the puzzle when
+// there isn't one. A failed command prints its error and the usage and exits.
+pub fn main(usage: &str, run_command: impl FnOnce(&[String]) -> Result<(), String>, answer: impl FnOnce()) {
+    let args = env::args().skip(1).collect::<Vec<String>>();
+
+    if args.is_empty() {
+        answer();
+        return;
+    }
+
+    if let Err(e) = run_command(&args) {
+        eprintln!("error: {}", e);
+        eprintln!("{}", usage);
+        process::exit(1);
+    }
+}
+
+#[test]
+fn test_input_and_command() {
+    let args = |args: &[&str]| args.iter().map(|a| a.to_string()).collect::<Vec<String>>();
+
+    let given = args(&["--input", "example.txt", "solve", "-x"]);
+    let mut rest = given.iter();
+    assert_eq!(Ok(("example.txt", &given[2])), input_and_command(&mut rest));
+    assert_eq!(Some(&given[3]), rest.next());
+
+    let given = args(&["solve"]);
+    assert_eq!(Ok(("input/problem.txt", &given[0])), input_and_command(&mut given.iter()));
+
+    assert_eq!(Err("--input needs a value".to_string()), input_and_command(&mut args(&["--input"]).iter()));
+    assert_eq!(Err("missing command".to_string()), input_and_command(&mut args(&[]).iter()));
+    assert_eq!(Err("invalid number: 1x".to_string()), parse_number::<u32>("1x"));
+}
@@ -0,0 +1,4 @@
+// What the days with more than the puzzle's two answers share: their command
+// line and writing animations.
+pub mod animation;
+pub mod cli;
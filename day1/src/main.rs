@@ -9,15 +9,17 @@ fn calculate(file: &str, count: usize) -> i32 {
     let mut results: BTreeSet<i32> = BTreeSet::new();
 
     for line in io::BufReader::new(file).lines() {
-        if let Ok(line) = line {
-            match line.as_str() {
-                "" => {
-                    results.insert(acc);
-                    acc = 0;
-                },
-                _ => {
-                    acc += line.parse::<i32>().unwrap();
-                }
+        let Ok(line) = line else {
+            continue;
+        };
+
+        match line.as_str() {
+            "" => {
+                results.insert(acc);
+                acc = 0;
+            },
+            _ => {
+                acc += line.parse::<i32>().unwrap();
             }
         }
     }
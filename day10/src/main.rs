@@ -14,11 +14,11 @@ struct Cpu {
 impl Cpu {
     fn load(&mut self, i: InstructionType) {
         self.instruction = match i {
-            InstructionType::NOOP => Some(Instruction {
+            InstructionType::Noop => Some(Instruction {
                 cycles: 1,
                 result: self.x,
             }),
-            InstructionType::ADDX(v) => Some(Instruction {
+            InstructionType::Addx(v) => Some(Instruction {
                 cycles: 2,
                 result: self.x + v,
             }),
@@ -42,8 +42,8 @@ impl Cpu {
 }
 
 enum InstructionType {
-    NOOP,
-    ADDX(i32),
+    Noop,
+    Addx(i32),
 }
 
 struct Instruction {
@@ -60,7 +60,7 @@ impl Crt {
         let index = (cycle as usize - 1) % 40;
         let row = (cycle as usize - 1) / 40;
 
-        self.framebuffer.resize(row as usize + 1, String::new());
+        self.framebuffer.resize(row + 1, String::new());
 
         let mut c = '.';
 
@@ -79,9 +79,9 @@ fn load_instructions(file: &str) -> VecDeque<InstructionType> {
     for line in BufReader::new(file).lines().map(|l| l.unwrap()) {
         let tokens: Vec<&str> = line.split(" ").collect();
 
-        let instruction = match tokens.get(0).unwrap() {
-            &"noop" => InstructionType::NOOP,
-            &"addx" => InstructionType::ADDX(tokens.get(1).unwrap().parse::<i32>().unwrap()),
+        let instruction = match *tokens.first().unwrap() {
+            "noop" => InstructionType::Noop,
+            "addx" => InstructionType::Addx(tokens.get(1).unwrap().parse::<i32>().unwrap()),
             _ => panic!("unexpected instruction"),
         };
 
@@ -22,7 +22,7 @@ fn pop(lines: &mut VecDeque<String>) -> String {
     lines.pop_front().unwrap().trim().to_string()
 }
 
-fn load_items(item_string: &String) -> VecDeque<u64> {
+fn load_items(item_string: &str) -> VecDeque<u64> {
     // Starting items: 79, 98
     let tokens: Vec<&str> = item_string.split(" ").collect();
 
@@ -32,7 +32,7 @@ fn load_items(item_string: &String) -> VecDeque<u64> {
         .collect::<VecDeque<u64>>()
 }
 
-fn load_operation(operation_string: &String) -> Operation {
+fn load_operation(operation_string: &str) -> Operation {
     // Operation: new = old * 19
     let tokens: Vec<&str> = operation_string.split(" ").collect();
 
@@ -41,8 +41,8 @@ fn load_operation(operation_string: &String) -> Operation {
 
     if *value == "old" {
         match *operation {
-            "*" => Box::new(|x: u64| x.clone() * x),
-            "+" => Box::new(|x: u64| x.clone() + x),
+            "*" => Box::new(|x: u64| x * x),
+            "+" => Box::new(|x: u64| x + x),
             _ => panic!("unexpected operation"),
         }
     } else {
@@ -56,14 +56,14 @@ fn load_operation(operation_string: &String) -> Operation {
     }
 }
 
-fn load_test(test_string: &String) -> u64 {
+fn load_test(test_string: &str) -> u64 {
     // Test: divisible by 23
     let tokens: Vec<&str> = test_string.split(" ").collect();
 
     tokens.get(3).unwrap().parse::<u64>().unwrap()
 }
 
-fn load_throw(throw_string: &String) -> usize {
+fn load_throw(throw_string: &str) -> usize {
     // If [true|false]: throw to monkey 2
     let tokens: Vec<&str> = throw_string.split(" ").collect();
 
@@ -115,7 +115,7 @@ fn load_monkeys(file: &str) -> Vec<Monkey> {
     result
 }
 
-fn process_round(monkeys: &mut Vec<Monkey>, worry_management: &impl Fn(u64) -> u64) {
+fn process_round(monkeys: &mut [Monkey], worry_management: &impl Fn(u64) -> u64) {
     for i in 0..monkeys.len() {
         let mut to_throw = vec![];
 
@@ -144,7 +144,7 @@ fn process_round(monkeys: &mut Vec<Monkey>, worry_management: &impl Fn(u64) -> u
 fn calculate(file: &str) -> u64 {
     let mut monkeys = load_monkeys(file);
 
-    process_rounds(&mut monkeys, 20, &Box::new(|x: u64| x / 3))
+    process_rounds(&mut monkeys, 20, Box::new(|x: u64| x / 3))
 }
 
 fn calculate_part2(file: &str) -> u64 {
@@ -60,7 +60,7 @@ impl Display for Grid {
                 write!(f, "{}", p.display)?;
             }
 
-            write!(f, "\n")?;
+            writeln!(f)?;
         }
 
         Ok(())
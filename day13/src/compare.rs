@@ -0,0 +1,151 @@
+use std::cmp::Ordering;
+
+// A packet as it's written, commas and whitespace left out. Integers are the
+// digits themselves so they can be any length.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Token<'a> {
+    Open,
+    Close,
+    Integer(&'a [u8]),
+}
+
+pub struct Tokens<'a> {
+    text: &'a [u8],
+    pos: usize,
+}
+
+pub fn tokens(text: &str) -> Tokens<'_> {
+    Tokens {
+        text: text.as_bytes(),
+        pos: 0,
+    }
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        while let Some(c) = self.text.get(self.pos) {
+            self.pos += 1;
+
+            match c {
+                b'[' => return Some(Token::Open),
+                b']' => return Some(Token::Close),
+                b'0'..=b'9' => {
+                    let start = self.pos - 1;
+                    while self.text.get(self.pos).is_some_and(|c| c.is_ascii_digit()) {
+                        self.pos += 1;
+                    }
+
+                    return Some(Token::Integer(&self.text[start..self.pos]));
+                }
+                _ => (),
+            }
+        }
+
+        None
+    }
+}
+
+// without leading zeros the longer number is the bigger one, and numbers as
+// long as each other compare digit by digit
+fn compare_digits(left: &[u8], right: &[u8]) -> Ordering {
+    let trim = |d: &[u8]| -> usize { d.iter().take_while(|c| **c == b'0').count() };
+    let (left, right) = (&left[trim(left)..], &right[trim(right)..]);
+
+    left.len().cmp(&right.len()).then_with(|| left.cmp(right))
+}
+
+// One side's tokens along with the lists its integers have been wrapped in
+// to compare them with the other side's lists. Those close straight after
+// the integer does.
+struct Cursor<'a> {
+    tokens: Tokens<'a>,
+    current: Option<Token<'a>>,
+    wrapped: usize,
+    closing: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(text: &'a str) -> Cursor<'a> {
+        let mut tokens = tokens(text);
+
+        Cursor {
+            current: tokens.next(),
+            tokens,
+            wrapped: 0,
+            closing: 0,
+        }
+    }
+
+    fn advance(&mut self) {
+        if let Some(Token::Integer(_)) = self.current {
+            self.closing += std::mem::take(&mut self.wrapped);
+        }
+
+        self.current = match self.closing {
+            0 => self.tokens.next(),
+            _ => {
+                self.closing -= 1;
+                Some(Token::Close)
+            }
+        };
+    }
+}
+
+// Compares two packets a token at a time straight from their text, without
+// parsing them into trees or copying anything.
+pub fn compare(left: &str, right: &str) -> Ordering {
+    let (mut l, mut r) = (Cursor::new(left), Cursor::new(right));
+
+    loop {
+        match (l.current, r.current) {
+            (None, None) => return Ordering::Equal,
+            (None, _) => return Ordering::Less,
+            (_, None) => return Ordering::Greater,
+            (Some(Token::Integer(a)), Some(Token::Integer(b))) => match compare_digits(a, b) {
+                Ordering::Equal => {
+                    l.advance();
+                    r.advance();
+                }
+                result => return result,
+            },
+            (Some(Token::Open), Some(Token::Open)) | (Some(Token::Close), Some(Token::Close)) => {
+                l.advance();
+                r.advance();
+            }
+            // whichever list runs out first
+            (Some(Token::Close), _) => return Ordering::Less,
+            (_, Some(Token::Close)) => return Ordering::Greater,
+            // the integer is compared as a list of just itself
+            (Some(Token::Integer(_)), Some(Token::Open)) => {
+                l.wrapped += 1;
+                r.advance();
+            }
+            (Some(Token::Open), Some(Token::Integer(_))) => {
+                l.advance();
+                r.wrapped += 1;
+            }
+        }
+    }
+}
+
+#[test]
+fn test_matches_tree() {
+    let mut rng = inputgen::Rng::new(13);
+
+    for _ in 0..2000 {
+        let left = inputgen::day13::packet(&mut rng, 4, 4, 3);
+        let right = inputgen::day13::packet(&mut rng, 4, 4, 3);
+
+        let expected = crate::load_packet_str(&left).cmp(&crate::load_packet_str(&right));
+        assert_eq!(expected, compare(&left, &right), "{} vs {}", left, right);
+    }
+
+    assert_eq!(Ordering::Less, compare("[[[7]],3]", "[7,[[[[4]]]]]"));
+    assert_eq!(
+        Ordering::Greater,
+        compare("[123456789012345678901234567890]", "[[99]]")
+    );
+    assert_eq!(Ordering::Equal, compare("[007, [ ]]", "[7,[]]"));
+}
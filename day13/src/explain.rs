@@ -0,0 +1,254 @@
+use std::{cmp::Ordering, fmt::Display};
+
+use itertools::{EitherOrBoth, Itertools};
+
+use crate::Packet;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+impl Display for Side {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Side::Left => write!(f, "left"),
+            Side::Right => write!(f, "right"),
+        }
+    }
+}
+
+// A packet borrowed from the ones being compared, an integer put in a list
+// is the one element slice around it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Value<'a> {
+    Integer(u64),
+    List(&'a [Packet]),
+}
+
+impl<'a> Value<'a> {
+    fn of(packet: &'a Packet) -> Value<'a> {
+        match packet {
+            Packet::Integer(i) => Value::Integer(*i),
+            Packet::List(list) => Value::List(list),
+        }
+    }
+}
+
+impl Display for Value<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Integer(i) => write!(f, "{}", i),
+            Value::List(list) => write!(f, "[{}]", list.iter().join(",")),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Event<'a> {
+    Compare(Value<'a>, Value<'a>),
+    // the side's integer and the list it was put in
+    Promote(Side, Value<'a>),
+    // the last two decide the order, the side is the one that's smaller or
+    // ran out
+    Smaller(Side),
+    RanOut(Side),
+}
+
+// What happened along with the indices into each list that led to it, and
+// how far in it's written out. That's one more for every promotion as well
+// as every list.
+#[derive(Debug, PartialEq)]
+pub struct Step<'a> {
+    pub path: Vec<usize>,
+    pub depth: usize,
+    pub event: Event<'a>,
+}
+
+pub struct Explanation<'a> {
+    pub steps: Vec<Step<'a>>,
+    pub order: Ordering,
+}
+
+impl<'a> Explanation<'a> {
+    // the comparison the order came from, none if the packets are equal
+    pub fn deciding(&self) -> Option<&Step<'a>> {
+        self.steps
+            .last()
+            .filter(|s| matches!(s.event, Event::Smaller(_) | Event::RanOut(_)))
+    }
+}
+
+// Compares the packets with the same rules as Ord for Packet, noting down
+// every step on the way.
+pub fn explain<'a>(left: &'a Packet, right: &'a Packet) -> Explanation<'a> {
+    let mut steps = vec![];
+    let order = walk(left, right, &mut vec![], 0, &mut steps);
+
+    Explanation { steps, order }
+}
+
+fn walk<'a>(
+    left: &'a Packet,
+    right: &'a Packet,
+    path: &mut Vec<usize>,
+    depth: usize,
+    steps: &mut Vec<Step<'a>>,
+) -> Ordering {
+    let mut step = |depth: usize, event: Event<'a>| {
+        steps.push(Step {
+            path: path.clone(),
+            depth,
+            event,
+        })
+    };
+
+    step(depth, Event::Compare(Value::of(left), Value::of(right)));
+
+    match (left, right) {
+        (Packet::Integer(l), Packet::Integer(r)) => {
+            let order = l.cmp(r);
+            match order {
+                Ordering::Less => step(depth + 1, Event::Smaller(Side::Left)),
+                Ordering::Greater => step(depth + 1, Event::Smaller(Side::Right)),
+                Ordering::Equal => (),
+            }
+            order
+        }
+        (Packet::Integer(_), Packet::List(r)) => {
+            let left = std::slice::from_ref(left);
+            step(depth + 1, Event::Promote(Side::Left, Value::List(left)));
+            step(
+                depth + 1,
+                Event::Compare(Value::List(left), Value::of(right)),
+            );
+            walk_lists(left, r, path, depth + 1, steps)
+        }
+        (Packet::List(l), Packet::Integer(_)) => {
+            let right = std::slice::from_ref(right);
+            step(depth + 1, Event::Promote(Side::Right, Value::List(right)));
+            step(
+                depth + 1,
+                Event::Compare(Value::of(left), Value::List(right)),
+            );
+            walk_lists(l, right, path, depth + 1, steps)
+        }
+        (Packet::List(l), Packet::List(r)) => walk_lists(l, r, path, depth, steps),
+    }
+}
+
+// the items of two lists compared at depth, in turn until one decides
+fn walk_lists<'a>(
+    left: &'a [Packet],
+    right: &'a [Packet],
+    path: &mut Vec<usize>,
+    depth: usize,
+    steps: &mut Vec<Step<'a>>,
+) -> Ordering {
+    for (i, item) in left.iter().zip_longest(right.iter()).enumerate() {
+        let (order, ran_out) = match item {
+            EitherOrBoth::Both(l, r) => {
+                path.push(i);
+                let order = walk(l, r, path, depth + 1, steps);
+                path.pop();
+                (order, None)
+            }
+            EitherOrBoth::Left(_) => (Ordering::Greater, Some(Side::Right)),
+            EitherOrBoth::Right(_) => (Ordering::Less, Some(Side::Left)),
+        };
+
+        if let Some(side) = ran_out {
+            steps.push(Step {
+                path: path.clone(),
+                depth: depth + 1,
+                event: Event::RanOut(side),
+            });
+        }
+        if order != Ordering::Equal {
+            return order;
+        }
+    }
+
+    Ordering::Equal
+}
+
+// written out the way the puzzle walks through its example
+impl Display for Explanation<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let verdict = |side: &Side| match side {
+            Side::Left => "so inputs are in the right order",
+            Side::Right => "so inputs are not in the right order",
+        };
+        let capitalised = |side: &Side| match side {
+            Side::Left => "Left",
+            Side::Right => "Right",
+        };
+
+        for step in self.steps.iter() {
+            write!(f, "{}- ", "  ".repeat(step.depth))?;
+
+            match &step.event {
+                Event::Compare(left, right) => writeln!(f, "Compare {} vs {}", left, right)?,
+                Event::Promote(side, list) => writeln!(
+                    f,
+                    "Mixed types; convert {} to {} and retry comparison",
+                    side, list
+                )?,
+                Event::Smaller(side) => writeln!(
+                    f,
+                    "{} side is smaller, {}",
+                    capitalised(side),
+                    verdict(side)
+                )?,
+                Event::RanOut(side) => writeln!(
+                    f,
+                    "{} side ran out of items, {}",
+                    capitalised(side),
+                    verdict(side)
+                )?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_walkthrough() {
+    let pairs = crate::load_packets("input/example.txt").unwrap();
+
+    let walkthrough = pairs
+        .iter()
+        .enumerate()
+        .map(|(i, p)| format!("== Pair {} ==\n{}", i + 1, explain(&p.left, &p.right)))
+        .join("\n");
+
+    assert_eq!(
+        std::fs::read_to_string("input/example_walkthrough.txt").unwrap(),
+        walkthrough
+    );
+
+    let explanation = explain(&pairs[1].left, &pairs[1].right);
+    assert_eq!(
+        Some(&Step {
+            path: vec![1, 0],
+            depth: 4,
+            event: Event::Smaller(Side::Left)
+        }),
+        explanation.deciding()
+    );
+
+    let mut rng = inputgen::Rng::new(50);
+    for _ in 0..500 {
+        let left = crate::load_packet_str(&inputgen::day13::packet(&mut rng, 4, 4, 3));
+        let right = crate::load_packet_str(&inputgen::day13::packet(&mut rng, 4, 4, 3));
+
+        let explanation = explain(&left, &right);
+        assert_eq!(left.cmp(&right), explanation.order);
+        assert_eq!(
+            explanation.order == Ordering::Equal,
+            explanation.deciding().is_none()
+        );
+    }
+}
@@ -1,18 +1,50 @@
+mod compare;
+mod explain;
+mod parse;
+
 use std::{
-    collections::VecDeque,
+    cmp::Ordering,
     fmt::Display,
     fs::File,
-    io::{BufRead, BufReader},
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::Path,
+    time::Instant,
 };
 
+use common::cli::{input_and_command, next_value, parse_number};
+use inputgen::Rng;
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+const DIVIDERS: [&str; 2] = ["[[2]]", "[[6]]"];
+
+// Parsing, printing and comparing trees all recurse into every list, this
+// keeps them well inside the stack. Comparing the text doesn't recurse so it
+// has no limit.
+const MAX_DEPTH: usize = 256;
+
+const USAGE: &str = "usage:
+  day13 [--input FILE] solve [-comparator tree|text] [-divider PACKET]...
+  day13 [--input FILE] bench [-pairs N] [-depth N] [-length N] [-seed N]
+  day13 [--input FILE] export OUTPUT.json|OUTPUT.txt
+  day13 [--input FILE] explain [-pair N]...
 
-#[derive(Clone, Eq, PartialEq)]
+explain walks through the pairs given, or every pair not in the right order
+
+files ending in .json hold a list of {\"left\": PACKET, \"right\": PACKET} pairs
+
+packets can be nested 256 lists deep, or any depth in a text file solved with
+-comparator text";
+
+// JSON has the same numbers and arrays, so a packet is written to it as is
+#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
 enum Packet {
-    Integer(u32),
+    Integer(u64),
     List(Vec<Packet>),
 }
 
+#[derive(Serialize, Deserialize)]
 struct PacketPair {
     left: Packet,
     right: Packet,
@@ -25,6 +57,23 @@ impl Display for PacketPair {
     }
 }
 
+impl Packet {
+    // how many lists deep it goes, without recursing so any depth is fine
+    fn depth(&self) -> usize {
+        let mut deepest = 0;
+        let mut open = vec![(self, 0)];
+
+        while let Some((packet, depth)) = open.pop() {
+            if let Packet::List(items) = packet {
+                deepest = deepest.max(depth + 1);
+                open.extend(items.iter().map(|p| (p, depth + 1)));
+            }
+        }
+
+        deepest
+    }
+}
+
 impl Display for Packet {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -48,77 +97,207 @@ impl Display for Packet {
     }
 }
 
-fn load_packet_list(chars: &mut impl Iterator<Item = char>) -> Packet {
-    let mut result = Vec::new();
+// each pair's text, their packets on the lines before a blank one
+fn pair_lines(lines: &[String]) -> Result<Vec<(String, String)>, String> {
+    let mut pairs = Vec::new();
 
-    let mut number = String::new();
-    while let Some(c) = chars.next() {
-        if c.is_digit(10) {
-            number.push(c);
-        } else if c == ',' {
-            if !number.is_empty() {
-                result.push(Packet::Integer(number.parse::<u32>().unwrap()));
-                number.clear();
+    for (i, chunk) in lines.chunks(3).enumerate() {
+        match chunk {
+            [left, right, ..] if chunk.get(2).is_none_or(|l| l.trim().is_empty()) => {
+                pairs.push((left.clone(), right.clone()))
             }
-        } else if c == '[' {
-            result.push(load_packet_list(chars));
-        } else if c == ']' {
-            if !number.is_empty() {
-                result.push(Packet::Integer(number.parse::<u32>().unwrap()));
+            [_, _, _] => {
+                return Err(format!(
+                    "line {}: expected a blank line between pairs",
+                    i * 3 + 3
+                ))
             }
-            break;
+            _ => return Err(format!("line {}: packet without a pair", i * 3 + 1)),
         }
     }
 
-    Packet::List(result)
+    Ok(pairs)
 }
 
-fn load_packet(line: &String) -> Packet {
-    let mut iter = line.chars();
+// the same errors as parse_pairs, for comparing the text as it is
+fn check_pairs(pairs: &[(String, String)]) -> Result<(), String> {
+    let check =
+        |text: &str, line: usize| parse::check(text).map_err(|e| format!("line {}: {}", line, e));
 
-    // consume the first [
-    iter.next();
+    for (i, (left, right)) in pairs.iter().enumerate() {
+        check(left, i * 3 + 1)?;
+        check(right, i * 3 + 2)?;
+    }
 
-    load_packet_list(&mut iter)
+    Ok(())
 }
 
-fn load_packet_str(line: &str) -> Packet {
-    load_packet(&line.to_string())
+fn parse_pairs(pairs: &[(String, String)]) -> Result<Vec<PacketPair>, String> {
+    let parse =
+        |text: &str, line: usize| parse::parse(text).map_err(|e| format!("line {}: {}", line, e));
+
+    pairs
+        .iter()
+        .enumerate()
+        .map(|(i, (left, right))| {
+            Ok(PacketPair {
+                left: parse(left, i * 3 + 1)?,
+                right: parse(right, i * 3 + 2)?,
+            })
+        })
+        .collect()
+}
+
+fn is_json(file: &str) -> bool {
+    Path::new(file).extension().is_some_and(|e| e == "json")
+}
+
+fn too_deep() -> String {
+    format!("packets can't be nested more than {} lists deep", MAX_DEPTH)
+}
+
+// how deep the arrays and objects go, leaving out anything in strings
+fn json_depth(text: &str) -> usize {
+    let (mut depth, mut deepest) = (0usize, 0);
+    let (mut in_string, mut escaped) = (false, false);
+
+    for c in text.chars() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            _ if in_string => (),
+            '[' | '{' => {
+                depth += 1;
+                deepest = deepest.max(depth);
+            }
+            ']' | '}' => depth = depth.saturating_sub(1),
+            _ => (),
+        }
+    }
+
+    deepest
+}
+
+// Any JSON numbers and arrays make packets, but like in the text a pair's
+// packets have to be lists and no deeper than MAX_DEPTH. That's checked
+// before they're read in, as anything deeper could run out of stack.
+fn load_json(text: &str) -> Result<Vec<PacketPair>, String> {
+    // the list of pairs and the pair itself are outside the packets
+    if json_depth(text) > MAX_DEPTH + 2 {
+        return Err(too_deep());
+    }
+
+    let mut deserializer = serde_json::Deserializer::from_str(text);
+    deserializer.disable_recursion_limit();
+
+    let pairs = Vec::<PacketPair>::deserialize(&mut deserializer)
+        .and_then(|pairs| deserializer.end().map(|_| pairs))
+        .map_err(|e| e.to_string())?;
+
+    for (i, pair) in pairs.iter().enumerate() {
+        for (side, packet) in [("left", &pair.left), ("right", &pair.right)] {
+            if let Packet::Integer(_) = packet {
+                return Err(format!(
+                    "pair {}: the {} packet has to be a list",
+                    i + 1,
+                    side
+                ));
+            }
+        }
+    }
+
+    Ok(pairs)
+}
+
+// Pairs from either the puzzle's text or a JSON list of pairs, each an
+// object with left and right packets.
+fn load_packets(file: &str) -> Result<Vec<PacketPair>, String> {
+    if is_json(file) {
+        return std::fs::read_to_string(file)
+            .map_err(|e| e.to_string())
+            .and_then(|text| load_json(&text))
+            .map_err(|e| format!("{}: {}", file, e));
+    }
+
+    let f = File::open(file).map_err(|e| format!("{}: {}", file, e))?;
+
+    let lines = BufReader::new(f)
+        .lines()
+        .map(|l| l.unwrap())
+        .collect::<Vec<String>>();
+
+    pair_lines(&lines)
+        .and_then(|pairs| parse_pairs(&pairs))
+        .map_err(|e| format!("{}: {}", file, e))
 }
 
-fn load_packets(file: &str) -> Vec<PacketPair> {
-    let file = File::open(file).unwrap();
+// the text of each pair of packets, checked but not parsed, or written out
+// again when they're JSON
+fn load_pair_lines(file: &str) -> Result<Vec<(String, String)>, String> {
+    if is_json(file) {
+        let pairs = load_packets(file)?;
+        return Ok(pairs
+            .iter()
+            .map(|p| (p.left.to_string(), p.right.to_string()))
+            .collect());
+    }
 
-    let mut lines: VecDeque<String> = BufReader::new(file).lines().map(|l| l.unwrap()).collect();
+    let f = File::open(file).map_err(|e| format!("{}: {}", file, e))?;
+    let lines = BufReader::new(f)
+        .lines()
+        .map(|l| l.unwrap())
+        .collect::<Vec<String>>();
 
-    let mut packets = Vec::new();
-    while !lines.is_empty() {
-        packets.push(PacketPair {
-            left: load_packet(&lines.pop_front().unwrap()),
-            right: load_packet(&lines.pop_front().unwrap()),
-        });
+    pair_lines(&lines)
+        .and_then(|pairs| check_pairs(&pairs).map(|_| pairs))
+        .map_err(|e| format!("{}: {}", file, e))
+}
 
-        // there might be a blank line to consume
-        if !lines.is_empty() {
-            lines.pop_front();
+// JSON gets a pair to a line, pretty printing packets takes a line for every
+// integer
+fn save_packets(pairs: &[PacketPair], file: &str) -> Result<(), String> {
+    // the same limit as loading them again
+    for (i, pair) in pairs.iter().enumerate() {
+        if pair.left.depth().max(pair.right.depth()) > MAX_DEPTH {
+            return Err(format!("{}: pair {}: {}", file, i + 1, too_deep()));
         }
     }
 
-    packets
+    let text = if is_json(file) {
+        let lines = pairs
+            .iter()
+            .map(|p| serde_json::to_string(p).map(|p| format!("  {}", p)))
+            .collect::<Result<Vec<String>, _>>()
+            .map_err(|e| format!("{}: {}", file, e))?;
+
+        format!("[\n{}\n]\n", lines.join(",\n"))
+    } else {
+        pairs
+            .iter()
+            .map(|p| p.to_string())
+            .collect::<Vec<String>>()
+            .join("\n")
+    };
+
+    let f = File::create(file).map_err(|e| format!("{}: {}", file, e))?;
+    let mut out = BufWriter::new(f);
+
+    out.write_all(text.as_bytes())
+        .and_then(|_| out.flush())
+        .map_err(|e| format!("{}: {}", file, e))
+}
+
+fn load_packet_str(line: &str) -> Packet {
+    parse::parse(line).unwrap()
 }
 
 impl Ord for Packet {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         match (self, other) {
-            (Packet::Integer(left), Packet::Integer(right)) => {
-                return left.cmp(right);
-            }
-            (Packet::Integer(_), Packet::List(_)) => {
-                return Packet::List(vec![self.clone()]).cmp(other);
-            }
-            (Packet::List(_), Packet::Integer(_)) => {
-                return self.cmp(&Packet::List(vec![other.clone()]));
-            }
+            (Packet::Integer(left), Packet::Integer(right)) => left.cmp(right),
+            (Packet::Integer(_), Packet::List(_)) => Packet::List(vec![self.clone()]).cmp(other),
+            (Packet::List(_), Packet::Integer(_)) => self.cmp(&Packet::List(vec![other.clone()])),
             (Packet::List(left), Packet::List(right)) => {
                 for item in left.iter().zip_longest(right.iter()) {
                     let result = match item {
@@ -147,47 +326,271 @@ impl PartialOrd for Packet {
     }
 }
 
-fn calculate(file: &str) -> u32 {
-    let packets = load_packets(file);
-
+// the sum of the 1-based indices of the pairs in the right order
+fn in_order(packets: &[PacketPair]) -> u32 {
     let mut result = 0;
     for (i, p) in packets.iter().enumerate() {
-        match p.left.cmp(&p.right) {
-            std::cmp::Ordering::Less => result += i as u32 + 1,
-            _ => (),
+        if p.left.cmp(&p.right) == std::cmp::Ordering::Less {
+            result += i as u32 + 1
         }
     }
 
     result
 }
 
+fn calculate(file: &str) -> u32 {
+    in_order(&load_packets(file).unwrap())
+}
+
+// The 1-based place each divider would end up at if they were sorted in
+// with the packets: one more than the packets and the other dividers less
+// than it. A divider goes in front of anything equal to it that came later,
+// so only the other dividers given before it count.
+fn divider_positions<T>(
+    packets: &[T],
+    dividers: &[T],
+    cmp: impl Fn(&T, &T) -> Ordering,
+) -> Vec<usize> {
+    dividers
+        .iter()
+        .enumerate()
+        .map(|(i, divider)| {
+            let packets = packets
+                .iter()
+                .filter(|p| cmp(p, divider) == Ordering::Less)
+                .count();
+            let dividers = dividers
+                .iter()
+                .enumerate()
+                .filter(|(j, d)| match cmp(d, divider) {
+                    Ordering::Less => true,
+                    Ordering::Equal => *j < i,
+                    Ordering::Greater => false,
+                })
+                .count();
+
+            packets + dividers + 1
+        })
+        .collect()
+}
+
+// the dividers' positions multiplied together, enough of them go past what
+// fits in 64 bits
+fn decoder_key(positions: &[usize]) -> Result<u64, String> {
+    positions
+        .iter()
+        .try_fold(1u64, |key, p| key.checked_mul(*p as u64))
+        .ok_or("the decoder key doesn't fit in 64 bits".to_string())
+}
+
+fn default_dividers() -> Vec<Packet> {
+    DIVIDERS.iter().map(|d| load_packet_str(d)).collect()
+}
+
 fn calculate_part2(file: &str) -> u32 {
-    let mut packets = Vec::new();
-    for p in load_packets(file) {
-        packets.push(p.left);
-        packets.push(p.right);
+    let packets = load_packets(file)
+        .unwrap()
+        .into_iter()
+        .flat_map(|p| [p.left, p.right])
+        .collect::<Vec<Packet>>();
+
+    divider_positions(&packets, &default_dividers(), Packet::cmp)
+        .iter()
+        .map(|p| *p as u32)
+        .product()
+}
+
+// both parts comparing the packets' text instead of parsed trees, giving the
+// pairs in order and where each divider goes
+fn calculate_text(file: &str, dividers: &[&str]) -> Result<(u32, Vec<usize>), String> {
+    let pairs = load_pair_lines(file)?;
+
+    let mut result = 0;
+    for (i, (left, right)) in pairs.iter().enumerate() {
+        if compare::compare(left, right) == Ordering::Less {
+            result += i as u32 + 1;
+        }
     }
 
-    packets.push(load_packet_str("[[2]]"));
-    packets.push(load_packet_str("[[6]]"));
+    let packets = pairs
+        .iter()
+        .flat_map(|(left, right)| [left.as_str(), right.as_str()])
+        .collect::<Vec<&str>>();
+    let positions = divider_positions(&packets, dividers, |a, b| compare::compare(a, b));
 
-    packets.sort();
+    Ok((result, positions))
+}
 
-    let mut result = 1;
-    for (i, p) in packets.iter().enumerate() {
-        if *p == load_packet_str("[[2]]") {
-            result *= i as u32 + 1;
-        } else if *p == load_packet_str("[[6]]") {
-            result *= i as u32 + 1;
+fn generated_pairs(pairs: usize, depth: usize, length: usize, seed: u64) -> Vec<(String, String)> {
+    let mut rng = Rng::new(seed);
+
+    (0..pairs)
+        .map(|_| {
+            (
+                inputgen::day13::packet(&mut rng, depth, length, 100),
+                inputgen::day13::packet(&mut rng, depth, length, 100),
+            )
+        })
+        .collect()
+}
+
+// Times comparing every pair with the trees, parsing included and not, and
+// straight from the text.
+fn bench(pairs: &[(String, String)]) -> Result<(), String> {
+    let bytes = pairs.iter().map(|(l, r)| l.len() + r.len()).sum::<usize>();
+    println!("pairs: {}, text: {} bytes", pairs.len(), bytes);
+
+    let start = Instant::now();
+    let trees = parse_pairs(pairs)?;
+    let parsed = start.elapsed();
+    let start = Instant::now();
+    let tree = trees
+        .iter()
+        .map(|p| p.left.cmp(&p.right))
+        .collect::<Vec<Ordering>>();
+    let compared = start.elapsed();
+    println!(
+        "tree: {:?} ({:?} parsing, {:?} comparing)",
+        parsed + compared,
+        parsed,
+        compared
+    );
+
+    let start = Instant::now();
+    let text = pairs
+        .iter()
+        .map(|(left, right)| compare::compare(left, right))
+        .collect::<Vec<Ordering>>();
+    println!("text: {:?}", start.elapsed());
+
+    let less = text.iter().filter(|o| **o == Ordering::Less).count();
+    let agree = if tree == text { "agree" } else { "DISAGREE" };
+    println!("{} pairs in order, comparators {}", less, agree);
+
+    Ok(())
+}
+
+fn run_command(args: &[String]) -> Result<(), String> {
+    let mut args = args.iter();
+    let (input, command) = input_and_command(&mut args)?;
+
+    let mut comparator = "tree";
+    let mut pairs = None;
+    let mut depth = 8;
+    let mut length = 10;
+    let mut seed = 13;
+    let mut output = None;
+    let mut dividers = Vec::new();
+    let mut explained = Vec::new();
+
+    while let Some(arg) = args.next() {
+        match (command.as_str(), arg.as_str()) {
+            ("solve", "-comparator") => comparator = next_value(&mut args, arg)?,
+            ("solve", "-divider") => dividers.push(next_value(&mut args, arg)?.as_str()),
+            ("bench", "-pairs") => pairs = Some(parse_number(next_value(&mut args, arg)?)?),
+            ("bench", "-depth") => depth = parse_number(next_value(&mut args, arg)?)?,
+            ("bench", "-length") => length = parse_number(next_value(&mut args, arg)?)?,
+            ("bench", "-seed") => seed = parse_number(next_value(&mut args, arg)?)?,
+            ("export", a) if !a.starts_with('-') => output = Some(a),
+            ("explain", "-pair") => explained.push(parse_number(next_value(&mut args, arg)?)?),
+            (_, a) => return Err(format!("unknown option for {}: {}", command, a)),
         }
     }
 
-    result
+    match command.as_str() {
+        "solve" => {
+            if dividers.is_empty() {
+                dividers = DIVIDERS.to_vec();
+            }
+
+            // the same errors whichever comparator is used, but the text one
+            // only checks the packets and never builds them
+            let (result, positions) = match comparator {
+                "tree" => {
+                    let divider_packets = dividers
+                        .iter()
+                        .map(|d| parse::parse(d).map_err(|e| format!("divider {}: {}", d, e)))
+                        .collect::<Result<Vec<Packet>, String>>()?;
+
+                    let pairs = load_packets(input)?;
+                    let result = in_order(&pairs);
+                    let packets = pairs
+                        .into_iter()
+                        .flat_map(|p| [p.left, p.right])
+                        .collect::<Vec<Packet>>();
+
+                    (
+                        result,
+                        divider_positions(&packets, &divider_packets, Packet::cmp),
+                    )
+                }
+                "text" => {
+                    for d in dividers.iter() {
+                        parse::check(d).map_err(|e| format!("divider {}: {}", d, e))?;
+                    }
+
+                    calculate_text(input, &dividers)?
+                }
+                c => return Err(format!("unknown comparator: {}", c)),
+            };
+
+            println!("result: {}", result);
+            for (divider, position) in dividers.iter().zip(positions.iter()) {
+                println!("divider {}: {}", divider, position);
+            }
+            println!("result part 2: {}", decoder_key(&positions)?);
+        }
+        "bench" => match pairs {
+            Some(pairs) => bench(&generated_pairs(pairs, depth, length, seed))?,
+            None => bench(&load_pair_lines(input)?)?,
+        },
+        "export" => {
+            let output = output.ok_or("export needs an output file")?;
+            let pairs = load_packets(input)?;
+
+            save_packets(&pairs, output)?;
+            println!("{} pairs written to {}", pairs.len(), output);
+        }
+        "explain" => {
+            let pairs = load_packets(input)?;
+
+            if explained.is_empty() {
+                explained = (1..=pairs.len())
+                    .filter(|i| {
+                        let pair = &pairs[i - 1];
+                        explain::explain(&pair.left, &pair.right).order != Ordering::Less
+                    })
+                    .collect();
+            }
+
+            for (n, i) in explained.iter().enumerate() {
+                let pair = (i.checked_sub(1))
+                    .and_then(|i| pairs.get(i))
+                    .ok_or(format!("there's no pair {}", i))?;
+                let explanation = explain::explain(&pair.left, &pair.right);
+
+                if n > 0 {
+                    println!();
+                }
+                println!("== Pair {} ==", i);
+                print!("{}", explanation);
+                match explanation.deciding() {
+                    Some(step) => println!("(decided at index path {:?})", step.path),
+                    None => println!("(the packets are equal)"),
+                }
+            }
+        }
+        c => return Err(format!("unknown command: {}", c)),
+    }
+
+    Ok(())
 }
 
 fn main() {
-    println!("result: {}", calculate("input/problem.txt"));
-    println!("result part 2: {}", calculate_part2("input/problem.txt"));
+    common::cli::main(USAGE, run_command, || {
+        println!("result: {}", calculate("input/problem.txt"));
+        println!("result part 2: {}", calculate_part2("input/problem.txt"));
+    });
 }
 
 #[test]
@@ -208,3 +611,123 @@ fn cmp() {
         load_packet_str("[9]").cmp(&load_packet_str("[[]]"))
     );
 }
+
+#[test]
+fn test_text_comparator() {
+    assert_eq!(
+        Ok((13, vec![10, 14])),
+        calculate_text("input/example.txt", &DIVIDERS)
+    );
+}
+
+#[test]
+fn test_divider_positions() {
+    let packets = load_packets("input/example.txt")
+        .unwrap()
+        .into_iter()
+        .flat_map(|p| [p.left, p.right])
+        .collect::<Vec<Packet>>();
+
+    // a stable sort with the dividers first gives the same places, "[]" is
+    // also one of the packets
+    let dividers = ["[[6]]", "[]", "[[2]]", "[10]", "[[2]]"].map(load_packet_str);
+    let mut sorted = dividers.iter().chain(packets.iter()).collect::<Vec<_>>();
+    sorted.sort();
+    let expected = dividers
+        .iter()
+        .map(|d| sorted.iter().position(|p| std::ptr::eq(*p, d)).unwrap() + 1)
+        .collect::<Vec<usize>>();
+
+    assert_eq!(
+        expected,
+        divider_positions(&packets, &dividers, Packet::cmp)
+    );
+    assert_eq!(vec![16, 1, 11, 21, 12], expected);
+
+    assert_eq!(Ok(44352), decoder_key(&expected));
+    assert_eq!(
+        Err("the decoder key doesn't fit in 64 bits".to_string()),
+        decoder_key(&[1 << 32, 1 << 32])
+    );
+}
+
+#[test]
+fn test_json_round_trip() {
+    let pairs = load_packets("input/example.txt").unwrap();
+
+    let path = std::env::temp_dir().join(format!("day13-{}.json", std::process::id()));
+    let file = path.to_str().unwrap();
+    save_packets(&pairs, file).unwrap();
+    let json = std::fs::read_to_string(&path).unwrap();
+    let loaded = load_packets(file).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(json.starts_with("[\n  {\"left\":[1,1,3,1,1],\"right\":[1,1,5,1,1]},\n"));
+    assert!(pairs
+        .iter()
+        .zip(loaded.iter())
+        .all(|(a, b)| a.left == b.left && a.right == b.right));
+    assert_eq!(pairs.len(), loaded.len());
+
+    let lines = ["[1]", "[2]", "", "[3]"].map(|l| l.to_string());
+    assert_eq!(
+        Err("line 4: packet without a pair".to_string()),
+        pair_lines(&lines)
+    );
+    let pairs = pair_lines(&["[1]", "[2,x]"].map(|l| l.to_string())).unwrap();
+    assert_eq!(
+        Some("line 2: column 4: expected a list or an integer, found 'x'".to_string()),
+        parse_pairs(&pairs).err()
+    );
+    assert_eq!(parse_pairs(&pairs).err(), check_pairs(&pairs).err());
+
+    // as deep as packets go, past serde_json's default limit, and an
+    // integer where a pair needs a list
+    let deep = format!("{}{}", "[".repeat(MAX_DEPTH), "]".repeat(MAX_DEPTH));
+    let pairs = parse_pairs(&[(deep.clone(), "[]".to_string())]).unwrap();
+    save_packets(&pairs, file).unwrap();
+    let loaded = load_packets(file);
+    std::fs::write(&path, "[{\"left\": 5, \"right\": [1]}]").unwrap();
+    let integer = load_packets(file);
+    let deeper = format!("[{{\"left\": [{}], \"right\": [1]}}]", deep);
+    std::fs::write(&path, deeper).unwrap();
+    let too_deep_json = load_packets(file);
+
+    let mut packet = Packet::List(vec![]);
+    for _ in 0..MAX_DEPTH {
+        packet = Packet::List(vec![packet]);
+    }
+    let saved = save_packets(
+        &[PacketPair {
+            left: Packet::List(vec![]),
+            right: packet,
+        }],
+        file,
+    );
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(deep, loaded.unwrap()[0].left.to_string());
+    assert_eq!(
+        Some(format!(
+            "{}: pair 1: the left packet has to be a list",
+            file
+        )),
+        integer.err()
+    );
+    assert_eq!(
+        Some(format!("{}: {}", file, too_deep())),
+        too_deep_json.err()
+    );
+    assert_eq!(
+        Some(format!("{}: pair 1: {}", file, too_deep())),
+        saved.err()
+    );
+    assert_eq!(
+        Some(format!(
+            "line 1: column {}: {}, found '['",
+            MAX_DEPTH + 1,
+            too_deep()
+        )),
+        parse_pairs(&[(format!("[{}]", deep), "[]".to_string())]).err()
+    );
+}
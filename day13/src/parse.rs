@@ -0,0 +1,242 @@
+use crate::{too_deep, Packet, MAX_DEPTH};
+
+// Reads a packet the way JSON would: a list of integers and lists with
+// whitespace allowed around anything, and nothing else, nested no more than
+// MAX_DEPTH lists deep. Errors point at the column they were found in.
+pub fn parse(text: &str) -> Result<Packet, String> {
+    read(text, Parser::packet)
+}
+
+// The same errors as parse without building the packet. It keeps count of
+// the open lists instead of recursing, so there's no limit to how deep they
+// go either.
+pub fn check(text: &str) -> Result<(), String> {
+    read(text, Parser::skip_packet)
+}
+
+fn read<'a, T>(
+    text: &'a str,
+    packet: impl FnOnce(&mut Parser<'a>) -> Result<T, String>,
+) -> Result<T, String> {
+    let mut parser = Parser {
+        text,
+        pos: 0,
+        depth: 0,
+    };
+
+    parser.skip_whitespace();
+    if parser.peek() != Some('[') {
+        return Err(parser.error("a packet has to be a list"));
+    }
+
+    let packet = packet(&mut parser)?;
+
+    parser.skip_whitespace();
+    match parser.peek() {
+        None => Ok(packet),
+        Some(_) => Err(parser.error("unexpected text after the packet")),
+    }
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+    // the lists parse is in, skip_packet keeps its own count
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek().filter(|c| c.is_whitespace()) {
+            self.pos += c.len_utf8();
+        }
+    }
+
+    fn error(&self, message: &str) -> String {
+        let found = match self.peek() {
+            Some(c) => format!("'{}'", c),
+            None => "the end".to_string(),
+        };
+
+        format!(
+            "column {}: {}, found {}",
+            self.text[..self.pos].chars().count() + 1,
+            message,
+            found
+        )
+    }
+
+    fn packet(&mut self) -> Result<Packet, String> {
+        self.skip_whitespace();
+
+        match self.peek() {
+            Some('[') => self.list(),
+            Some(c) if c.is_ascii_digit() => self.integer(),
+            _ => Err(self.error("expected a list or an integer")),
+        }
+    }
+
+    fn list(&mut self) -> Result<Packet, String> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error(&too_deep()));
+        }
+
+        self.depth += 1;
+        let list = self.items();
+        self.depth -= 1;
+
+        list
+    }
+
+    fn items(&mut self) -> Result<Packet, String> {
+        self.pos += 1;
+        self.skip_whitespace();
+
+        let mut items = vec![];
+        if self.peek() == Some(']') {
+            self.pos += 1;
+            return Ok(Packet::List(items));
+        }
+
+        loop {
+            items.push(self.packet()?);
+            self.skip_whitespace();
+
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some(']') => {
+                    self.pos += 1;
+                    return Ok(Packet::List(items));
+                }
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    // packet and list in one loop, depth is how many lists are still open
+    fn skip_packet(&mut self) -> Result<(), String> {
+        let mut depth = 0;
+
+        loop {
+            self.skip_whitespace();
+
+            match self.peek() {
+                Some('[') => {
+                    self.pos += 1;
+                    self.skip_whitespace();
+
+                    if self.peek() != Some(']') {
+                        depth += 1;
+                        continue;
+                    }
+                    self.pos += 1;
+                }
+                Some(c) if c.is_ascii_digit() => {
+                    self.integer()?;
+                }
+                _ => return Err(self.error("expected a list or an integer")),
+            }
+
+            // an item has ended, along with any lists closed straight after it
+            loop {
+                if depth == 0 {
+                    return Ok(());
+                }
+
+                self.skip_whitespace();
+                match self.peek() {
+                    Some(',') => {
+                        self.pos += 1;
+                        break;
+                    }
+                    Some(']') => {
+                        self.pos += 1;
+                        depth -= 1;
+                    }
+                    _ => return Err(self.error("expected ',' or ']'")),
+                }
+            }
+        }
+    }
+
+    fn integer(&mut self) -> Result<Packet, String> {
+        let start = self.pos;
+        let digits = self.text[start..]
+            .bytes()
+            .take_while(|c| c.is_ascii_digit())
+            .count();
+
+        // JSON doesn't allow them, so neither does the puzzle
+        if digits > 1 && self.text[start..].starts_with('0') {
+            return Err(self.error("integers can't have leading zeros"));
+        }
+
+        let value = self.text[start..start + digits]
+            .parse::<u64>()
+            .map_err(|_| self.error("integer too large"))?;
+        self.pos += digits;
+
+        Ok(Packet::Integer(value))
+    }
+}
+
+#[test]
+fn test_strict_parsing() {
+    assert_eq!(
+        Ok("[1,[2,[]],18446744073709551615]"),
+        parse(" [ 1 , [2,[ ]],\t18446744073709551615 ] ")
+            .map(|p| p.to_string())
+            .as_deref()
+    );
+
+    let error = |text: &str| {
+        assert_eq!(check(text).err(), parse(text).err());
+        parse(text).unwrap_err()
+    };
+    assert_eq!("column 1: a packet has to be a list, found '1'", error("1"));
+    assert_eq!(
+        "column 1: a packet has to be a list, found the end",
+        error("")
+    );
+    assert_eq!("column 4: expected ',' or ']', found '3'", error("[1 3]"));
+    assert_eq!(
+        "column 4: expected a list or an integer, found ']'",
+        error("[1,]")
+    );
+    assert_eq!(
+        "column 2: expected a list or an integer, found 'a'",
+        error("[a]")
+    );
+    assert_eq!(
+        "column 5: expected ',' or ']', found the end",
+        error("[[1]")
+    );
+    assert_eq!(
+        "column 4: unexpected text after the packet, found ']'",
+        error("[1]]")
+    );
+    assert_eq!(
+        "column 2: integers can't have leading zeros, found '0'",
+        error("[07]")
+    );
+    assert_eq!(
+        "column 2: integer too large, found '1'",
+        error("[18446744073709551616]")
+    );
+
+    // checking goes deeper than the stack would let parse
+    let deep = format!("{}1{}", "[".repeat(1_000_000), "]".repeat(1_000_000));
+    assert_eq!(Ok(()), check(&deep));
+    assert_eq!(
+        format!("column {}: {}, found '['", MAX_DEPTH + 1, too_deep()),
+        parse(&deep).unwrap_err()
+    );
+    assert_eq!(
+        "column 1000002: expected ',' or ']', found the end",
+        check(&deep[..1_000_001]).unwrap_err()
+    );
+}
@@ -0,0 +1,104 @@
+use std::collections::BTreeMap;
+
+use crate::{Point, Substance};
+
+// Where everything in the cave is, anything never set is air.
+pub trait Cave {
+    // min and max are the corners of everywhere sand can get to
+    fn with_rocks(
+        rocks: &BTreeMap<Point, Substance>,
+        min: Point,
+        max: Point,
+    ) -> Result<Self, String>
+    where
+        Self: Sized;
+
+    fn get(&self, p: Point) -> Substance;
+
+    fn set(&mut self, p: Point, substance: Substance);
+}
+
+impl Cave for BTreeMap<Point, Substance> {
+    fn with_rocks(rocks: &BTreeMap<Point, Substance>, _: Point, _: Point) -> Result<Self, String> {
+        Ok(rocks.clone())
+    }
+
+    fn get(&self, p: Point) -> Substance {
+        *BTreeMap::get(self, &p).unwrap_or(&Substance::Air)
+    }
+
+    fn set(&mut self, p: Point, substance: Substance) {
+        self.insert(p, substance);
+    }
+}
+
+// A row-major array covering a fixed box, a lookup is an index instead of a
+// walk down the tree. Reading outside the box gives air so sand can fall past
+// its sides.
+pub struct Dense {
+    min: Point,
+    width: i32,
+    height: i32,
+    cells: Vec<Substance>,
+}
+
+impl Dense {
+    fn index(&self, p: Point) -> Option<usize> {
+        let (x, y) = (p.x - self.min.x, p.y - self.min.y);
+        if !(0..self.width).contains(&x) || !(0..self.height).contains(&y) {
+            return None;
+        }
+
+        Some(y as usize * self.width as usize + x as usize)
+    }
+}
+
+impl Cave for Dense {
+    // A sparse cave can cover far more cells than there's memory for, the
+    // map backend only stores the rocks.
+    fn with_rocks(
+        rocks: &BTreeMap<Point, Substance>,
+        min: Point,
+        max: Point,
+    ) -> Result<Self, String> {
+        let (width, height) = (max.x - min.x + 1, max.y - min.y + 1);
+        let too_big = || {
+            format!(
+                "a dense cave of {} by {} cells doesn't fit in memory",
+                width, height
+            )
+        };
+
+        let count = (width as usize)
+            .checked_mul(height as usize)
+            .ok_or_else(too_big)?;
+        let mut cells = Vec::new();
+        cells.try_reserve_exact(count).map_err(|_| too_big())?;
+        cells.resize(count, Substance::Air);
+
+        let mut cave = Dense {
+            min,
+            width,
+            height,
+            cells,
+        };
+
+        for (p, substance) in rocks.iter() {
+            cave.set(*p, *substance);
+        }
+
+        Ok(cave)
+    }
+
+    fn get(&self, p: Point) -> Substance {
+        self.index(p).map_or(Substance::Air, |i| self.cells[i])
+    }
+
+    fn set(&mut self, p: Point, substance: Substance) {
+        let i = self
+            .index(p)
+            .unwrap_or_else(|| panic!("{:?} is outside the cave", p));
+
+        self.cells[i] = substance;
+    }
+}
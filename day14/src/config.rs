@@ -0,0 +1,361 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    cave::Cave, cave_box, check_floor, fall_by, highest_y, Floor, Point, Substance, SOURCE,
+};
+
+pub const SAND_MOVES: [(i32, i32); 3] = [(0, 1), (-1, 1), (1, 1)];
+const WATER_MOVES: [(i32, i32); 3] = [(0, 1), (-1, 0), (1, 0)];
+
+// What comes out of a source and the moves it tries each step, taking the
+// first one into air. Every move is a single step down, diagonally down or
+// sideways, and never straight back to where it just came from, so a
+// particle can't go round in circles.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Particle {
+    pub substance: Substance,
+    pub moves: Vec<(i32, i32)>,
+}
+
+impl Particle {
+    pub fn sand() -> Particle {
+        Particle {
+            substance: Substance::Sand,
+            moves: SAND_MOVES.to_vec(),
+        }
+    }
+
+    // sand or water, optionally followed by the moves to try as
+    // :DX,DY/DX,DY/...
+    pub fn parse(spec: &str) -> Result<Particle, String> {
+        let (name, moves) = match spec.split_once(':') {
+            Some((name, moves)) => (name, Some(moves)),
+            None => (spec, None),
+        };
+
+        let (substance, default) = match name {
+            "sand" => (Substance::Sand, SAND_MOVES),
+            "water" => (Substance::Water, WATER_MOVES),
+            _ => return Err(format!("unknown particle: {}", name)),
+        };
+
+        let moves = match moves {
+            Some(moves) => moves
+                .split('/')
+                .map(parse_move)
+                .collect::<Result<Vec<_>, String>>()?,
+            None => default.to_vec(),
+        };
+
+        Ok(Particle { substance, moves })
+    }
+}
+
+fn parse_move(s: &str) -> Result<(i32, i32), String> {
+    let (dx, dy) = s.split_once(',').ok_or(format!("invalid move: {}", s))?;
+    let dx = dx
+        .parse::<i32>()
+        .map_err(|_| format!("invalid move: {}", s))?;
+    let dy = dy
+        .parse::<i32>()
+        .map_err(|_| format!("invalid move: {}", s))?;
+
+    match (dx, dy) {
+        (-1..=1, 1) | (-1 | 1, 0) => Ok((dx, dy)),
+        _ => Err(format!("{} isn't a single step down or sideways", s)),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Source {
+    pub at: Point,
+    pub particle: Particle,
+}
+
+impl Source {
+    // X,Y then optionally :PARTICLE, sand when it's left out
+    pub fn parse(spec: &str) -> Result<Source, String> {
+        let (at, particle) = match spec.split_once(':') {
+            Some((at, particle)) => (at, Particle::parse(particle)?),
+            None => (spec, Particle::sand()),
+        };
+
+        let (x, y) = at
+            .split_once(',')
+            .ok_or(format!("invalid source: {}", spec))?;
+        let parse = |v: &str| {
+            v.parse::<i32>()
+                .map_err(|_| format!("invalid source: {}", spec))
+        };
+
+        Ok(Source {
+            at: Point {
+                x: parse(x)?,
+                y: parse(y)?,
+            },
+            particle,
+        })
+    }
+}
+
+// The sources take turns dropping a particle each until every one of them
+// has either lost one off the edge of the cave or filled up. With a floor
+// it's that far below the lowest rock, otherwise particles fall into the
+// abyss.
+pub struct Config {
+    pub sources: Vec<Source>,
+    pub floor: Option<u32>,
+}
+
+// Everything that came to rest in the order it did, and how many came from
+// each source.
+pub struct Outcome {
+    pub floor: Floor,
+    pub sources: Vec<Point>,
+    pub settled: Vec<(Point, Substance)>,
+    pub counts: Vec<usize>,
+}
+
+enum Step {
+    Move(Point),
+    Rest,
+    Lost,
+}
+
+impl Config {
+    pub fn puzzle(part: u32) -> Config {
+        Config {
+            sources: vec![Source {
+                at: SOURCE,
+                particle: Particle::sand(),
+            }],
+            floor: (part == 2).then_some(2),
+        }
+    }
+
+    // The box particles are kept in, anything leaving it is lost. Nothing
+    // can get further out than the rocks before falling into the abyss, and
+    // with a floor, where there are no sideways moves, it piles up no wider
+    // than the cone under each source.
+    fn bounds(
+        &self,
+        rocks: &BTreeMap<Point, Substance>,
+        floor: Floor,
+    ) -> Result<(Point, Point), String> {
+        let points = rocks.keys().chain(self.sources.iter().map(|s| &s.at));
+        let (mut min, mut max) = (
+            Point {
+                x: i32::MAX,
+                y: i32::MAX,
+            },
+            Point {
+                x: i32::MIN,
+                y: i32::MIN,
+            },
+        );
+
+        for p in points {
+            min = Point {
+                x: min.x.min(p.x),
+                y: min.y.min(p.y),
+            };
+            max = Point {
+                x: max.x.max(p.x),
+                y: max.y.max(p.y),
+            };
+        }
+
+        let (min_x, max_x, max_y) = match floor {
+            Floor::Abyss(_) => (min.x.checked_sub(1), max.x.checked_add(1), Some(max.y)),
+            Floor::Floor(y) => {
+                let mut spread = Some((min.x, max.x));
+                for s in self.sources.iter() {
+                    spread = spread.and_then(|(min_x, max_x)| {
+                        let depth = y.checked_sub(s.at.y)?;
+                        Some((
+                            min_x.min(s.at.x.checked_sub(depth)?),
+                            max_x.max(s.at.x.checked_add(depth)?),
+                        ))
+                    });
+                }
+
+                (spread.map(|s| s.0), spread.map(|s| s.1), Some(y - 1))
+            }
+        };
+
+        cave_box(min_x, Some(min.y), max_x, max_y)
+    }
+
+    pub fn run<C: Cave>(&self, rocks: &BTreeMap<Point, Substance>) -> Result<Outcome, String> {
+        let lowest = highest_y(rocks);
+        let floor = match self.floor {
+            Some(depth) => Floor::Floor(
+                i32::try_from(depth)
+                    .ok()
+                    .and_then(|depth| lowest.checked_add(depth))
+                    .ok_or(format!(
+                        "a floor {} below the lowest rock at {} is out of range",
+                        depth, lowest
+                    ))?,
+            ),
+            None => Floor::Abyss(lowest),
+        };
+
+        for s in self.sources.iter() {
+            check_floor(s.at, floor)?;
+        }
+
+        if let Floor::Floor(_) = floor {
+            // nothing stops a particle sliding along the floor, it would
+            // never come to rest
+            if let Some(s) = self
+                .sources
+                .iter()
+                .find(|s| s.particle.moves.iter().any(|(_, dy)| *dy == 0))
+            {
+                return Err(format!(
+                    "the source at {},{} moves sideways, which can't be used with a floor",
+                    s.at.x, s.at.y
+                ));
+            }
+        }
+
+        let (min, max) = self.bounds(rocks, floor)?;
+        let mut state = C::with_rocks(rocks, min, max)?;
+
+        // Each source's particles follow the same path as the last one until
+        // they reach where it came to rest, so like the path strategy each
+        // source keeps the path and starts from the position before that.
+        // Other sources can fill part of it in though, and then it's only
+        // good up to there.
+        let mut paths = self
+            .sources
+            .iter()
+            .map(|s| match state.get(s.at) {
+                Substance::Air => vec![s.at],
+                _ => vec![],
+            })
+            .collect::<Vec<Vec<Point>>>();
+
+        let mut outcome = Outcome {
+            floor,
+            sources: self.sources.iter().map(|s| s.at).collect(),
+            settled: vec![],
+            counts: vec![0; self.sources.len()],
+        };
+
+        let mut active = paths.iter().map(|p| !p.is_empty()).collect::<Vec<bool>>();
+        while active.contains(&true) {
+            for (i, source) in self.sources.iter().enumerate() {
+                while active[i] {
+                    let Some(p) = paths[i].last().copied() else {
+                        active[i] = false;
+                        break;
+                    };
+                    let previous = paths[i].len().checked_sub(2).map(|j| paths[i][j]);
+
+                    match step(&state, &source.particle, p, previous, floor, (min, max)) {
+                        Step::Move(next) => paths[i].push(next),
+                        Step::Lost => active[i] = false,
+                        Step::Rest => {
+                            state.set(p, source.particle.substance);
+                            outcome.settled.push((p, source.particle.substance));
+                            outcome.counts[i] += 1;
+
+                            for path in paths.iter_mut() {
+                                if let Some(k) = path.iter().position(|q| *q == p) {
+                                    path.truncate(k);
+                                }
+                            }
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(outcome)
+    }
+}
+
+fn step<C: Cave>(
+    state: &C,
+    particle: &Particle,
+    p: Point,
+    previous: Option<Point>,
+    floor: Floor,
+    (min, max): (Point, Point),
+) -> Step {
+    match fall_by(state, p, &particle.moves, previous, floor) {
+        Some(next) if next.x < min.x || next.x > max.x || next.y > max.y => Step::Lost,
+        Some(next) => Step::Move(next),
+        None => Step::Rest,
+    }
+}
+
+#[test]
+fn test_puzzle_configs() {
+    let rocks = crate::load_rocks("input/example.txt").unwrap();
+
+    for (part, expected) in [(1, 24), (2, 93)] {
+        let outcome = Config::puzzle(part)
+            .run::<crate::cave::Dense>(&rocks)
+            .unwrap();
+        assert_eq!(vec![expected], outcome.counts);
+    }
+
+    assert_eq!(
+        Err("2,0 isn't a single step down or sideways".to_string()),
+        Particle::parse("sand:0,1/2,0")
+    );
+}
+
+#[test]
+fn test_sources_and_water() {
+    // water fills a cup a row at a time, then spills over the side and is
+    // lost
+    let cup =
+        crate::parse_rocks(["496,3 -> 496,6 -> 504,6 -> 504,3".to_string()].into_iter()).unwrap();
+    let config = Config {
+        sources: vec![Source::parse("500,0:water").unwrap()],
+        floor: None,
+    };
+    let outcome = config.run::<crate::cave::Dense>(&cup).unwrap();
+    assert_eq!(vec![21], outcome.counts);
+    assert!(outcome
+        .settled
+        .iter()
+        .all(|(p, s)| p.y >= 3 && *s == Substance::Water));
+
+    // sand rolling right first piles up the other way round
+    let rocks = crate::load_rocks("input/example.txt").unwrap();
+    let config = Config {
+        sources: vec![Source::parse("500,0:sand:0,1/1,1/-1,1").unwrap()],
+        floor: Some(2),
+    };
+    let outcome = config.run::<crate::cave::Dense>(&rocks).unwrap();
+    assert_eq!(Point { x: 501, y: 8 }, outcome.settled[1].0);
+    assert_eq!(93, outcome.counts[0]);
+
+    // two sand sources sharing one pile, the second blocked off by the
+    // first filling in above it
+    let config = Config {
+        sources: vec![
+            Source::parse("500,0").unwrap(),
+            Source::parse("499,1:sand").unwrap(),
+        ],
+        floor: Some(2),
+    };
+    let outcome = config.run::<crate::cave::Dense>(&rocks).unwrap();
+    assert_eq!(vec![66, 27], outcome.counts);
+
+    // water would slide along a floor forever
+    let config = Config {
+        sources: vec![Source::parse("500,0:water").unwrap()],
+        floor: Some(2),
+    };
+    assert_eq!(
+        Some("the source at 500,0 moves sideways, which can't be used with a floor".to_string()),
+        config.run::<crate::cave::Dense>(&rocks).err()
+    );
+}
@@ -1,21 +1,48 @@
+mod cave;
+mod config;
+mod render;
+
+use cave::{Cave, Dense};
+use common::cli::{input_and_command, next_value, parse_number};
+use config::{Config, Source, SAND_MOVES};
+use inputgen::Rng;
 use itertools::Itertools;
+use render::{Animation, Canvas};
 use std::{
     collections::BTreeMap,
     fs::File,
     io::{BufRead, BufReader},
+    path::Path,
+    time::Instant,
 };
 
+const USAGE: &str = "usage:
+  day14 [--input FILE] solve [-backend map|dense] [-strategy naive|path|sweep]
+  day14 [--input FILE] bench [-paths N] [-depth N] [-seed N]
+  day14 [--input FILE] simulate [SIMULATION]
+  day14 [--input FILE] draw [SIMULATION] [-grains N]
+  day14 [--input FILE] animate [SIMULATION] [-every GRAINS] [-delay SECONDS] [-scale N] OUTPUT.cast|OUTPUT.gif
+
+simulation: [-part 1|2] [-source X,Y[:PARTICLE]]... [-floor DEPTH]
+particle: sand or water, optionally with the moves to try as :DX,DY/DX,DY/...
+  sideways moves can't be used with a floor";
+
+const SOURCE: Point = Point { x: 500, y: 0 };
+
+const OUT_OF_RANGE: &str = "the cave doesn't fit in 32 bit coordinates";
+
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
 struct Point {
-    x: u32,
-    y: u32,
+    x: i32,
+    y: i32,
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 enum Substance {
     Air,
     Rock,
     Sand,
+    Water,
 }
 
 enum SandResult {
@@ -23,29 +50,44 @@ enum SandResult {
     Abyss,
 }
 
+#[derive(Clone, Copy)]
 enum Floor {
-    Abyss(u32),
-    Floor(u32),
+    Abyss(i32),
+    Floor(i32),
 }
 
-fn point_from_string(s: &str) -> Point {
-    let v = s
-        .split(",")
-        .map(|s| s.parse::<u32>().unwrap())
-        .collect::<Vec<u32>>();
-    let x = v[0];
-    let y = v[1];
-
-    Point { x, y }
+fn point_from_string(s: &str) -> Result<Point, String> {
+    let (x, y) = s.split_once(',').ok_or(format!("invalid point: {}", s))?;
+    let parse = |v: &str| {
+        v.parse::<i32>()
+            .map_err(|_| format!("invalid point: {}", s))
+    };
+
+    Ok(Point {
+        x: parse(x)?,
+        y: parse(y)?,
+    })
 }
 
-fn load_rock_line(result: &mut BTreeMap<Point, Substance>, line: &String) {
+fn load_rock_line(result: &mut BTreeMap<Point, Substance>, line: &str) -> Result<(), String> {
     let points = line
-        .split(" -> ")
-        .map(point_from_string)
-        .collect::<Vec<Point>>();
+        .split("->")
+        .map(|p| point_from_string(p.trim()))
+        .collect::<Result<Vec<Point>, String>>()?;
+
+    if points.len() < 2 {
+        return Err("a rock path needs at least two points".to_string());
+    }
 
     for (p1, p2) in points.iter().tuple_windows() {
+        // anything else would fill in the rectangle between them
+        if p1.x != p2.x && p1.y != p2.y {
+            return Err(format!(
+                "{},{} -> {},{} isn't horizontal or vertical",
+                p1.x, p1.y, p2.x, p2.y
+            ));
+        }
+
         let min_x = p1.x.min(p2.x);
         let max_x = p1.x.max(p2.x);
 
@@ -58,111 +100,457 @@ fn load_rock_line(result: &mut BTreeMap<Point, Substance>, line: &String) {
             }
         }
     }
-}
 
-fn load_rocks(file: &str) -> BTreeMap<Point, Substance> {
-    let file = File::open(file).unwrap();
+    Ok(())
+}
 
+fn parse_rocks(lines: impl Iterator<Item = String>) -> Result<BTreeMap<Point, Substance>, String> {
     let mut result = BTreeMap::new();
-    for line in BufReader::new(file).lines().map(|l| l.unwrap()) {
-        load_rock_line(&mut result, &line);
+    for (n, line) in lines.enumerate() {
+        load_rock_line(&mut result, &line).map_err(|e| format!("line {}: {}", n + 1, e))?;
     }
 
-    result
+    if result.is_empty() {
+        return Err("no rock paths".to_string());
+    }
+
+    Ok(result)
 }
 
-fn add_sand(state: &mut BTreeMap<Point, Substance>, p: Point, floor: Floor) -> SandResult {
-    let mut p = p;
+fn load_rocks(file: &str) -> Result<BTreeMap<Point, Substance>, String> {
+    let f = File::open(file).map_err(|e| format!("{}: {}", file, e))?;
 
-    loop {
-        let options = [
-            Point { x: p.x, y: p.y + 1 },
-            Point {
-                x: p.x - 1,
-                y: p.y + 1,
-            },
-            Point {
-                x: p.x + 1,
-                y: p.y + 1,
-            },
-        ];
-
-        let mut moved = false;
-        for o in options.iter() {
-            match *state.get(o).unwrap_or(&Substance::Air) {
-                Substance::Air => {
-                    p = *o;
-                    moved = true;
-                    break;
-                }
-                Substance::Rock | Substance::Sand => (),
-            }
-        }
+    parse_rocks(BufReader::new(f).lines().map(|l| l.unwrap()))
+        .map_err(|e| format!("{}: {}", file, e))
+}
 
-        if !moved {
-            break;
+// The way grains are dropped. Naive walks every grain down from the source,
+// path keeps the positions the last grain fell through and starts the next
+// one from where it came to rest. Sweep doesn't drop any with a floor, it
+// counts where sand can get to instead.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Strategy {
+    Naive,
+    Path,
+    Sweep,
+}
+
+impl Strategy {
+    const ALL: [Strategy; 3] = [Strategy::Naive, Strategy::Path, Strategy::Sweep];
+
+    fn name(self) -> &'static str {
+        match self {
+            Strategy::Naive => "naive",
+            Strategy::Path => "path",
+            Strategy::Sweep => "sweep",
         }
+    }
 
-        match floor {
-            Floor::Abyss(y) => {
-                if p.y > y {
-                    return SandResult::Abyss;
-                }
-            }
-            Floor::Floor(y) => {
-                if p.y + 1 == y {
-                    break;
-                }
-            }
-        };
+    fn parse(name: &str) -> Option<Strategy> {
+        Strategy::ALL.into_iter().find(|s| s.name() == name)
+    }
+}
+
+// Where a particle at p goes next trying each of the moves in turn, None
+// once it has come to rest. It never goes straight back to previous or into
+// the floor.
+fn fall_by<C: Cave>(
+    state: &C,
+    p: Point,
+    moves: &[(i32, i32)],
+    previous: Option<Point>,
+    floor: Floor,
+) -> Option<Point> {
+    moves
+        .iter()
+        .map(|(dx, dy)| Point {
+            x: p.x + dx,
+            y: p.y + dy,
+        })
+        .filter(|next| Some(*next) != previous)
+        .filter(|next| !matches!(floor, Floor::Floor(y) if next.y == y))
+        .find(|next| matches!(state.get(*next), Substance::Air))
+}
+
+// where a grain at p falls to next, None once it has come to rest
+fn fall<C: Cave>(state: &C, p: Point, floor: Floor) -> Option<Point> {
+    fall_by(state, p, &SAND_MOVES, None, floor)
+}
+
+fn add_sand<C: Cave>(state: &mut C, p: Point, floor: Floor) -> SandResult {
+    let mut p = p;
+
+    while let Some(next) = fall(state, p, floor) {
+        p = next;
+
+        if matches!(floor, Floor::Abyss(y) if p.y > y) {
+            return SandResult::Abyss;
+        }
     }
 
-    state.insert(p, Substance::Sand);
+    state.set(p, Substance::Sand);
 
     SandResult::Success(p)
 }
 
-fn highest_y(state: &BTreeMap<Point, Substance>) -> u32 {
-    state.iter().map(|(k, _v)| k.y).max().unwrap()
+fn highest_y(state: &BTreeMap<Point, Substance>) -> i32 {
+    state.keys().map(|k| k.y).max().unwrap()
 }
 
-fn calculate(file: &str) -> u32 {
-    let mut state = load_rocks(file);
+// The floor the sand ends up on has to be below the source, or no grain
+// would ever come to rest.
+fn check_floor(source: Point, floor: Floor) -> Result<(), String> {
+    match floor {
+        Floor::Floor(y) if source.y >= y => Err(format!(
+            "the source at {},{} isn't above the floor at {}",
+            source.x, source.y, y
+        )),
+        _ => Ok(()),
+    }
+}
 
-    let abyss = highest_y(&state);
+// The corners of everywhere sand can get to before the simulation stops.
+// With an abyss that's the rocks' width and one row below them, with a floor
+// it's the triangle the sand piles up into under the source.
+fn bounds(rocks: &BTreeMap<Point, Substance>, floor: Floor) -> Result<(Point, Point), String> {
+    let min_x = rocks.keys().map(|p| p.x).min().unwrap().min(SOURCE.x);
+    let max_x = rocks.keys().map(|p| p.x).max().unwrap().max(SOURCE.x);
+    let min_y = rocks.keys().map(|p| p.y).min().unwrap().min(SOURCE.y);
+
+    let (min_x, max_x, max_y) = match floor {
+        Floor::Abyss(y) => (min_x.checked_sub(1), max_x.checked_add(1), y.checked_add(1)),
+        Floor::Floor(y) => (
+            SOURCE.x.checked_sub(y).map(|x| x.min(min_x)),
+            SOURCE.x.checked_add(y).map(|x| x.max(max_x)),
+            Some(y),
+        ),
+    };
+
+    cave_box(min_x, Some(min_y), max_x, max_y)
+}
 
+// The box between those corners, as long as they and its width and height
+// all fit in 32 bits.
+fn cave_box(
+    min_x: Option<i32>,
+    min_y: Option<i32>,
+    max_x: Option<i32>,
+    max_y: Option<i32>,
+) -> Result<(Point, Point), String> {
+    let side = |min: i32, max: i32| max.checked_sub(min)?.checked_add(1);
+
+    match (min_x, min_y, max_x, max_y) {
+        (Some(min_x), Some(min_y), Some(max_x), Some(max_y))
+            if side(min_x, max_x).is_some() && side(min_y, max_y).is_some() =>
+        {
+            Ok((Point { x: min_x, y: min_y }, Point { x: max_x, y: max_y }))
+        }
+        _ => Err(OUT_OF_RANGE.to_string()),
+    }
+}
+
+// drops sand from the source until a grain falls into the abyss or comes to
+// rest on the source, returning how many grains came to rest
+fn fill<C: Cave>(
+    rocks: &BTreeMap<Point, Substance>,
+    floor: Floor,
+    strategy: Strategy,
+) -> Result<u32, String> {
+    check_floor(SOURCE, floor)?;
+
+    let (min, max) = bounds(rocks, floor)?;
+    let mut state = C::with_rocks(rocks, min, max)?;
+
+    Ok(match strategy {
+        Strategy::Naive => fill_naive(&mut state, floor),
+        Strategy::Sweep => match floor {
+            Floor::Floor(y) => sweep(&state, y),
+            // grains falling into the abyss don't fill a cone, those still
+            // have to be dropped
+            Floor::Abyss(_) => fill_path(&mut state, floor),
+        },
+        Strategy::Path => fill_path(&mut state, floor),
+    })
+}
+
+fn fill_naive<C: Cave>(state: &mut C, floor: Floor) -> u32 {
     let mut result = 0;
-    while let SandResult::Success(_) =
-        add_sand(&mut state, Point { x: 500, y: 0 }, Floor::Abyss(abyss))
-    {
+    while let SandResult::Success(p) = add_sand(state, SOURCE, floor) {
         result += 1;
+
+        if p == SOURCE {
+            break;
+        }
     }
 
     result
 }
 
-fn calculate_part2(file: &str) -> u32 {
-    let mut state = load_rocks(file);
-
-    let floor = highest_y(&state) + 2;
+// Everything on the path down to where a grain rests is still free for the
+// next one, which falls the same way until it reaches the spot the last one
+// took, so it can start from the position before that.
+fn fill_path<C: Cave>(state: &mut C, floor: Floor) -> u32 {
+    let mut path = vec![SOURCE];
 
     let mut result = 0;
-    while let SandResult::Success(p) =
-        add_sand(&mut state, Point { x: 500, y: 0 }, Floor::Floor(floor))
-    {
-        result += 1;
-        
-        if p == (Point{ x: 500, y: 0 }) {
-            break;
+    while let Some(p) = path.last().copied() {
+        match fall(state, p, floor) {
+            Some(next) if matches!(floor, Floor::Abyss(y) if next.y > y) => break,
+            Some(next) => path.push(next),
+            None => {
+                state.set(p, Substance::Sand);
+                result += 1;
+                path.pop();
+            }
+        }
+    }
+
+    result
+}
+
+// With a floor the sand ends up everywhere it can get to, and it can get to
+// anywhere that isn't rock with one of the three cells above it reachable,
+// so it can be counted a row at a time down from the source.
+fn sweep<C: Cave>(state: &C, floor: i32) -> u32 {
+    let left = SOURCE.x - floor;
+    let width = 2 * floor as usize + 1;
+
+    let source = (SOURCE.x - left) as usize;
+    let mut row = vec![false; width];
+    let mut next = vec![false; width];
+    row[source] = true;
+
+    let mut result = 1;
+    for y in 1..floor {
+        // nothing further out than y either side of the source is reachable
+        let span = source.saturating_sub(y as usize)..=(source + y as usize).min(width - 1);
+
+        for i in span {
+            let p = Point {
+                x: left + i as i32,
+                y,
+            };
+
+            next[i] = !matches!(state.get(p), Substance::Rock)
+                && row[i.saturating_sub(1)..=(i + 1).min(width - 1)].contains(&true);
+            result += next[i] as u32;
         }
+
+        std::mem::swap(&mut row, &mut next);
     }
 
     result
 }
 
+fn part1<C: Cave>(rocks: &BTreeMap<Point, Substance>, strategy: Strategy) -> Result<u32, String> {
+    fill::<C>(rocks, Floor::Abyss(highest_y(rocks)), strategy)
+}
+
+fn part2<C: Cave>(rocks: &BTreeMap<Point, Substance>, strategy: Strategy) -> Result<u32, String> {
+    let floor = highest_y(rocks).checked_add(2).ok_or(OUT_OF_RANGE)?;
+
+    fill::<C>(rocks, Floor::Floor(floor), strategy)
+}
+
+fn calculate(file: &str) -> u32 {
+    part1::<Dense>(&load_rocks(file).unwrap(), Strategy::Path).unwrap()
+}
+
+fn calculate_part2(file: &str) -> u32 {
+    part2::<Dense>(&load_rocks(file).unwrap(), Strategy::Path).unwrap()
+}
+
+fn generated_rocks(
+    paths: usize,
+    depth: i64,
+    seed: u64,
+) -> Result<BTreeMap<Point, Substance>, String> {
+    let spread = depth / 3;
+    if paths == 0 || depth < 3 {
+        return Err(format!(
+            "can't generate {} paths down to depth {}",
+            paths, depth
+        ));
+    }
+    let text = inputgen::day14::rock_paths(&mut Rng::new(seed), paths, 6, spread, depth);
+
+    parse_rocks(text.lines().map(|l| l.to_string()))
+}
+
+// times both parts with each strategy and the rocks in a map and in a dense
+// grid
+fn bench(rocks: &BTreeMap<Point, Substance>) -> Result<(), String> {
+    let floor = highest_y(rocks).checked_add(2).ok_or(OUT_OF_RANGE)?;
+    let (min, max) = bounds(rocks, Floor::Floor(floor))?;
+    println!(
+        "rocks: {}, cave: {}..{} x {}..{}",
+        rocks.len(),
+        min.x,
+        max.x,
+        min.y,
+        max.y
+    );
+
+    type Map = BTreeMap<Point, Substance>;
+    type Part = fn(&Map, Strategy) -> Result<u32, String>;
+    let backends: [(&str, Part, Part); 2] = [
+        ("map", part1::<Map>, part2::<Map>),
+        ("dense", part1::<Dense>, part2::<Dense>),
+    ];
+
+    for (strategy, (name, part1, part2)) in Strategy::ALL.into_iter().cartesian_product(backends) {
+        let start = Instant::now();
+        let result = part1(rocks, strategy)?;
+        let middle = Instant::now();
+        let result_part2 = part2(rocks, strategy)?;
+
+        println!(
+            "{} {}: part 1 {:?} ({}), part 2 {:?} ({})",
+            strategy.name(),
+            name,
+            middle - start,
+            result,
+            middle.elapsed(),
+            result_part2
+        );
+    }
+
+    Ok(())
+}
+
+fn run_command(args: &[String]) -> Result<(), String> {
+    let mut args = args.iter();
+    let (input, command) = input_and_command(&mut args)?;
+
+    let mut backend = "dense";
+    let mut strategy = Strategy::Path;
+    let mut paths = None;
+    let mut depth = 170;
+    let mut seed = 14;
+    let mut part = 1;
+    let mut sources = vec![];
+    let mut floor = None;
+    let mut grains_shown = None;
+    let mut every = 1;
+    let mut delay = 0.05;
+    let mut scale = 2;
+    let mut output = None;
+
+    while let Some(arg) = args.next() {
+        match (command.as_str(), arg.as_str()) {
+            ("solve", "-backend") => backend = next_value(&mut args, arg)?,
+            ("solve", "-strategy") => {
+                let value = next_value(&mut args, arg)?;
+                strategy = Strategy::parse(value).ok_or(format!("unknown strategy: {}", value))?;
+            }
+            ("bench", "-paths") => paths = Some(parse_number(next_value(&mut args, arg)?)?),
+            ("bench", "-depth") => depth = parse_number(next_value(&mut args, arg)?)?,
+            ("bench", "-seed") => seed = parse_number(next_value(&mut args, arg)?)?,
+            ("simulate" | "draw" | "animate", "-part") => {
+                let value = next_value(&mut args, arg)?;
+                part = parse_number(value)
+                    .ok()
+                    .filter(|p| [1, 2].contains(p))
+                    .ok_or(format!("invalid part: {}", value))?;
+            }
+            ("simulate" | "draw" | "animate", "-source") => {
+                sources.push(Source::parse(next_value(&mut args, arg)?)?)
+            }
+            ("simulate" | "draw" | "animate", "-floor") => {
+                floor = Some(parse_number(next_value(&mut args, arg)?)?)
+            }
+            ("draw", "-grains") => grains_shown = Some(parse_number(next_value(&mut args, arg)?)?),
+            ("animate", "-every") => every = parse_number(next_value(&mut args, arg)?)?,
+            ("animate", "-delay") => delay = parse_number(next_value(&mut args, arg)?)?,
+            ("animate", "-scale") => scale = parse_number(next_value(&mut args, arg)?)?,
+            ("animate", a) if !a.starts_with('-') => output = Some(a),
+            (_, a) => return Err(format!("unknown option for {}: {}", command, a)),
+        }
+    }
+
+    // the puzzle's source and floor unless they're given
+    let mut config = Config::puzzle(part);
+    if !sources.is_empty() {
+        config.sources = sources;
+    }
+    config.floor = floor.or(config.floor);
+
+    match command.as_str() {
+        "solve" => {
+            let rocks = load_rocks(input)?;
+
+            let (result, result_part2) = match backend {
+                "map" => (
+                    part1::<BTreeMap<Point, Substance>>(&rocks, strategy)?,
+                    part2::<BTreeMap<Point, Substance>>(&rocks, strategy)?,
+                ),
+                "dense" => (
+                    part1::<Dense>(&rocks, strategy)?,
+                    part2::<Dense>(&rocks, strategy)?,
+                ),
+                b => return Err(format!("unknown backend: {}", b)),
+            };
+
+            println!("result: {}", result);
+            println!("result part 2: {}", result_part2);
+        }
+        "bench" => match paths {
+            Some(paths) => bench(&generated_rocks(paths, depth, seed)?)?,
+            None => bench(&load_rocks(input)?)?,
+        },
+        "simulate" => {
+            let outcome = config.run::<Dense>(&load_rocks(input)?)?;
+
+            println!("source\tdrawn as\tat rest");
+            for (source, count) in config.sources.iter().zip(outcome.counts) {
+                println!(
+                    "{},{}\t{}\t{}",
+                    source.at.x, source.at.y, source.particle.substance, count
+                );
+            }
+        }
+        "draw" => {
+            let rocks = load_rocks(input)?;
+            let outcome = config.run::<Dense>(&rocks)?;
+            let total = outcome.settled.len();
+
+            // sized for the finished cave so the pictures line up
+            let shown = grains_shown.unwrap_or(total).min(total);
+            for row in Canvas::fitting(&rocks, &outcome).text(&rocks, &outcome, shown) {
+                println!("{}", row);
+            }
+            println!("{} of {} grains", shown, total);
+        }
+        "animate" => {
+            let output = output.ok_or("animate needs an output file")?;
+            if every == 0 || scale == 0 {
+                return Err("-every and -scale need to be at least 1".to_string());
+            }
+
+            let rocks = load_rocks(input)?;
+            let animation = Animation::new(&rocks, config.run::<Dense>(&rocks)?, every);
+            let path = Path::new(output);
+
+            match path.extension().and_then(|e| e.to_str()) {
+                Some("cast") => animation.write_cast(path, delay),
+                Some("gif") => animation.write_gif(path, delay, scale),
+                _ => return Err(format!("{} should end in .cast or .gif", output)),
+            }
+            .map_err(|e| format!("{}: {}", output, e))?;
+
+            println!("{} frames written to {}", animation.len(), output);
+        }
+        c => return Err(format!("unknown command: {}", c)),
+    }
+
+    Ok(())
+}
+
 fn main() {
-    println!("result: {}", calculate("input/problem.txt"));
-    println!("result part 2: {}", calculate_part2("input/problem.txt"));
+    common::cli::main(USAGE, run_command, || {
+        println!("result: {}", calculate("input/problem.txt"));
+        println!("result part 2: {}", calculate_part2("input/problem.txt"));
+    });
 }
 
 #[test]
@@ -174,3 +562,148 @@ fn test_example() {
 fn test_example_part2() {
     assert_eq!(93, calculate_part2("input/example.txt"));
 }
+
+#[test]
+fn test_backends_agree() {
+    type Map = BTreeMap<Point, Substance>;
+
+    for seed in 0..5 {
+        let rocks = generated_rocks(40, 60, seed).unwrap();
+
+        assert_eq!(
+            part1::<Map>(&rocks, Strategy::Naive),
+            part1::<Dense>(&rocks, Strategy::Naive)
+        );
+        assert_eq!(
+            part2::<Map>(&rocks, Strategy::Naive),
+            part2::<Dense>(&rocks, Strategy::Naive)
+        );
+    }
+}
+
+#[test]
+fn test_far_apart_rocks() {
+    type Map = BTreeMap<Point, Substance>;
+    let rocks = |lines: &[&str]| parse_rocks(lines.iter().map(|l| l.to_string())).unwrap();
+
+    // far more cells between the rocks than fit in memory, only the map
+    // can hold them
+    let far = rocks(&[
+        "-1000000000,5 -> -1000000000,6",
+        "1000000000,2000000000 -> 1000000000,2000000001",
+    ]);
+    assert_eq!(
+        Err("a dense cave of 2000000003 by 2000000003 cells doesn't fit in memory".to_string()),
+        part1::<Dense>(&far, Strategy::Path)
+    );
+
+    let sparse = rocks(&["-2000000000,5 -> -2000000000,6", "498,4 -> 498,6"]);
+    assert_eq!(Ok(0), part1::<Map>(&sparse, Strategy::Path));
+    assert_eq!(Ok(61), part2::<Map>(&sparse, Strategy::Path));
+}
+
+#[test]
+fn test_path_matches_naive() {
+    let example = load_rocks("input/example.txt").unwrap();
+    assert_eq!(Ok(24), part1::<Dense>(&example, Strategy::Naive));
+    assert_eq!(Ok(93), part2::<Dense>(&example, Strategy::Naive));
+
+    for seed in 0..5 {
+        let rocks = generated_rocks(40, 60, seed).unwrap();
+
+        assert_eq!(
+            part1::<Dense>(&rocks, Strategy::Naive),
+            part1::<Dense>(&rocks, Strategy::Path)
+        );
+        assert_eq!(
+            part2::<Dense>(&rocks, Strategy::Naive),
+            part2::<Dense>(&rocks, Strategy::Path)
+        );
+    }
+}
+
+#[test]
+fn test_sweep_matches_simulation() {
+    let example = load_rocks("input/example.txt").unwrap();
+    assert_eq!(Ok(93), part2::<Dense>(&example, Strategy::Sweep));
+
+    for seed in 0..5 {
+        let rocks = generated_rocks(40, 60, seed).unwrap();
+
+        assert_eq!(
+            part2::<Dense>(&rocks, Strategy::Path),
+            part2::<Dense>(&rocks, Strategy::Sweep)
+        );
+    }
+}
+
+#[test]
+fn test_rocks_out_of_reach() {
+    let rocks = |line: &str| parse_rocks([line.to_string()].into_iter()).unwrap();
+
+    // the floor under rocks all above the source is above it too
+    let above = rocks("498,-4 -> 498,-6 -> 496,-6");
+    assert_eq!(Ok(0), part1::<Dense>(&above, Strategy::Path));
+    for strategy in Strategy::ALL {
+        assert_eq!(
+            Err("the source at 500,0 isn't above the floor at -2".to_string()),
+            part2::<Dense>(&above, strategy)
+        );
+    }
+
+    let far = rocks("-2147483648,5 -> -2147483648,6");
+    assert_eq!(
+        Err(OUT_OF_RANGE.to_string()),
+        part1::<Dense>(&far, Strategy::Path)
+    );
+
+    let config = Config {
+        sources: vec![Source::parse("500,0").unwrap()],
+        floor: Some(3000000000),
+    };
+    assert_eq!(
+        Some("a floor 3000000000 below the lowest rock at 6 is out of range".to_string()),
+        config.run::<Dense>(&far).err()
+    );
+}
+
+#[test]
+fn test_rock_paths() {
+    let rocks = |lines: &[&str]| parse_rocks(lines.iter().map(|l| l.to_string())).map(|r| r.len());
+
+    assert_eq!(
+        Err("line 2: 498,4 -> 500,6 isn't horizontal or vertical".to_string()),
+        rocks(&["1,1 -> 1,2", "498,4 -> 500,6"])
+    );
+    assert_eq!(
+        Err("line 1: invalid point: 498;4".to_string()),
+        rocks(&["498;4 -> 498,6"])
+    );
+    assert_eq!(
+        Err("line 1: invalid point: ".to_string()),
+        rocks(&["498,4 -> "])
+    );
+    assert_eq!(
+        Err("line 1: a rock path needs at least two points".to_string()),
+        rocks(&["498,4"])
+    );
+    assert_eq!(Err("no rock paths".to_string()), rocks(&[]));
+
+    // the example moved to the left of x = 0 with the source at the origin
+    let shifted = parse_rocks(
+        ["-2,4 -> -2,6 -> -4,6", "3,4 -> 2,4 -> 2,9 -> -6,9"]
+            .iter()
+            .map(|l| l.to_string()),
+    )
+    .unwrap();
+    for (floor, expected) in [(None, 24), (Some(2), 93)] {
+        let config = Config {
+            sources: vec![Source::parse("0,0").unwrap()],
+            floor,
+        };
+        assert_eq!(
+            vec![expected],
+            config.run::<Dense>(&shifted).unwrap().counts
+        );
+    }
+}
@@ -0,0 +1,249 @@
+use std::{collections::BTreeMap, fmt::Display, io, path::Path};
+
+use common::animation::{Cast, Gif};
+
+use crate::{config::Outcome, Floor, Point, Substance};
+
+impl Display for Substance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let c = match self {
+            Substance::Air => '.',
+            Substance::Rock => '#',
+            Substance::Sand => 'o',
+            Substance::Water => '~',
+        };
+
+        write!(f, "{}", c)
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Cell {
+    Source,
+    Substance(Substance),
+}
+
+// The area drawn, just big enough for the rocks, the sources and everything
+// that came to rest, and down to the floor when there is one.
+pub struct Canvas {
+    min: Point,
+    max: Point,
+    floor: Option<i32>,
+}
+
+impl Canvas {
+    pub fn fitting(rocks: &BTreeMap<Point, Substance>, outcome: &Outcome) -> Canvas {
+        let mut canvas = Canvas {
+            min: outcome.sources[0],
+            max: outcome.sources[0],
+            floor: None,
+        };
+
+        let settled = outcome.settled.iter().map(|(p, _)| p);
+        for p in rocks.keys().chain(outcome.sources.iter()).chain(settled) {
+            canvas.min = Point {
+                x: canvas.min.x.min(p.x),
+                y: canvas.min.y.min(p.y),
+            };
+            canvas.max = Point {
+                x: canvas.max.x.max(p.x),
+                y: canvas.max.y.max(p.y),
+            };
+        }
+
+        if let Floor::Floor(y) = outcome.floor {
+            canvas.max.y = y;
+            canvas.floor = Some(y);
+        }
+
+        canvas
+    }
+
+    pub fn width(&self) -> usize {
+        (self.max.x - self.min.x + 1) as usize
+    }
+
+    pub fn height(&self) -> usize {
+        (self.max.y - self.min.y + 1) as usize
+    }
+
+    fn index(&self, p: Point) -> usize {
+        (p.y - self.min.y) as usize * self.width() + (p.x - self.min.x) as usize
+    }
+
+    // row-major from the top, sources are hidden once something covers them
+    fn cells(
+        &self,
+        rocks: &BTreeMap<Point, Substance>,
+        sources: &[Point],
+        settled: &[(Point, Substance)],
+    ) -> Vec<Cell> {
+        let mut cells = vec![Cell::Substance(Substance::Air); self.width() * self.height()];
+
+        for (p, substance) in rocks.iter() {
+            cells[self.index(*p)] = Cell::Substance(*substance);
+        }
+
+        if let Some(y) = self.floor {
+            let start = self.index(Point { x: self.min.x, y });
+            cells[start..start + self.width()].fill(Cell::Substance(Substance::Rock));
+        }
+
+        for p in sources {
+            cells[self.index(*p)] = Cell::Source;
+        }
+
+        for (p, substance) in settled {
+            cells[self.index(*p)] = Cell::Substance(*substance);
+        }
+
+        cells
+    }
+
+    // with just the first few of everything that came to rest
+    pub fn text(
+        &self,
+        rocks: &BTreeMap<Point, Substance>,
+        outcome: &Outcome,
+        shown: usize,
+    ) -> Vec<String> {
+        let cells = self.cells(rocks, &outcome.sources, &outcome.settled[..shown]);
+
+        text(&cells, self.width())
+    }
+}
+
+fn text(cells: &[Cell], width: usize) -> Vec<String> {
+    cells
+        .chunks(width)
+        .map(|row| {
+            row.iter()
+                .map(|c| match c {
+                    Cell::Source => "+".to_string(),
+                    Cell::Substance(s) => s.to_string(),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// The cave after every few particles have come to rest, and always once
+// they all have.
+pub struct Animation {
+    rocks: BTreeMap<Point, Substance>,
+    outcome: Outcome,
+    every: usize,
+    canvas: Canvas,
+}
+
+impl Animation {
+    pub fn new(rocks: &BTreeMap<Point, Substance>, outcome: Outcome, every: usize) -> Animation {
+        Animation {
+            canvas: Canvas::fitting(rocks, &outcome),
+            rocks: rocks.clone(),
+            outcome,
+            every,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.outcome.settled.len().div_ceil(self.every) + 1
+    }
+
+    // the cells for each frame, adding what came to rest since the last one
+    fn replay(&self, mut draw: impl FnMut(&[Cell]) -> io::Result<()>) -> io::Result<()> {
+        let mut cells = self.canvas.cells(&self.rocks, &self.outcome.sources, &[]);
+        draw(&cells)?;
+
+        for settled in self.outcome.settled.chunks(self.every) {
+            for (p, substance) in settled {
+                cells[self.canvas.index(*p)] = Cell::Substance(*substance);
+            }
+
+            draw(&cells)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn write_cast(&self, path: &Path, delay: f64) -> io::Result<()> {
+        let mut cast = Cast::create(path, self.canvas.width(), self.canvas.height(), delay)?;
+        self.replay(|cells| cast.frame(&text(cells, self.canvas.width())))?;
+
+        cast.finish()
+    }
+
+    // Draws each cell as a scale x scale square.
+    pub fn write_gif(&self, path: &Path, delay: f64, scale: usize) -> io::Result<()> {
+        // air, rock, sand, water then the sources
+        let palette = [
+            16, 16, 24, 110, 110, 110, 230, 190, 90, 60, 120, 230, 220, 40, 40,
+        ];
+        let colour = |c: &Cell| match c {
+            Cell::Substance(Substance::Air) => 0,
+            Cell::Substance(Substance::Rock) => 1,
+            Cell::Substance(Substance::Sand) => 2,
+            Cell::Substance(Substance::Water) => 3,
+            Cell::Source => 4,
+        };
+
+        let (width, height) = (self.canvas.width(), self.canvas.height());
+        let mut gif = Gif::create(path, width, height, scale, delay, &palette)?;
+        self.replay(|cells| gif.frame(&cells.iter().map(colour).collect::<Vec<u8>>()))
+    }
+}
+
+#[test]
+fn test_render_example() {
+    let rocks = crate::load_rocks("input/example.txt").unwrap();
+    let outcome = crate::config::Config::puzzle(1)
+        .run::<crate::cave::Dense>(&rocks)
+        .unwrap();
+
+    let expected = [
+        "......+...",
+        "..........",
+        "......o...",
+        ".....ooo..",
+        "....#ooo##",
+        "...o#ooo#.",
+        "..###ooo#.",
+        "....oooo#.",
+        ".o.ooooo#.",
+        "#########.",
+    ];
+    assert_eq!(
+        expected.to_vec(),
+        Canvas::fitting(&rocks, &outcome).text(&rocks, &outcome, 24)
+    );
+
+    // the first two grains, and the floor across the bottom for part 2
+    let outcome = crate::config::Config::puzzle(2)
+        .run::<crate::cave::Dense>(&rocks)
+        .unwrap();
+    let text = Canvas::fitting(&rocks, &outcome).text(&rocks, &outcome, 2);
+    assert_eq!(12, text.len());
+    assert_eq!(".........oo.#........", text[8]);
+    assert_eq!("#####################", text[11]);
+}
+
+#[test]
+fn test_cast() {
+    let rocks = crate::load_rocks("input/example.txt").unwrap();
+    let outcome = crate::config::Config::puzzle(1)
+        .run::<crate::cave::Dense>(&rocks)
+        .unwrap();
+    let animation = Animation::new(&rocks, outcome, 5);
+    // the empty cave then after every fifth of the 24 grains, and the last
+    assert_eq!(6, animation.len());
+
+    let path = std::env::temp_dir().join(format!("day14-{}.cast", std::process::id()));
+    animation.write_cast(&path, 0.1).unwrap();
+    let cast = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let lines = cast.lines().collect::<Vec<&str>>();
+    assert_eq!("{\"version\": 2, \"width\": 10, \"height\": 10}", lines[0]);
+    assert_eq!(7, lines.len());
+    assert!(lines[6].starts_with("[0.500, \"o\", \"\\u001b[H......+..."));
+}
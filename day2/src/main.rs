@@ -22,29 +22,29 @@ fn score(moves: (Move, Move)) -> i32 {
     let m = moves.1;
 
     match moves {
-        (Move::Rock, Move::Rock) => return score_result(m, Result::Draw),
-        (Move::Rock, Move::Paper) => return score_result(m, Result::Win),
-        (Move::Rock, Move::Scissors) => return score_result(m, Result::Lose),
-        (Move::Paper, Move::Rock) => return score_result(m, Result::Lose),
-        (Move::Paper, Move::Paper) => return score_result(m, Result::Draw),
-        (Move::Paper, Move::Scissors) => return score_result(m, Result::Win),
-        (Move::Scissors, Move::Rock) => return score_result(m, Result::Win),
-        (Move::Scissors, Move::Paper) => return score_result(m, Result::Lose),
-        (Move::Scissors, Move::Scissors) => return score_result(m, Result::Draw),
+        (Move::Rock, Move::Rock) => score_result(m, Result::Draw),
+        (Move::Rock, Move::Paper) => score_result(m, Result::Win),
+        (Move::Rock, Move::Scissors) => score_result(m, Result::Lose),
+        (Move::Paper, Move::Rock) => score_result(m, Result::Lose),
+        (Move::Paper, Move::Paper) => score_result(m, Result::Draw),
+        (Move::Paper, Move::Scissors) => score_result(m, Result::Win),
+        (Move::Scissors, Move::Rock) => score_result(m, Result::Win),
+        (Move::Scissors, Move::Paper) => score_result(m, Result::Lose),
+        (Move::Scissors, Move::Scissors) => score_result(m, Result::Draw),
     }
 }
 
 fn input(line: &str) -> (Move, Move) {
     match line {
-        "A X" => return (Move::Rock, Move::Scissors),
-        "A Y" => return (Move::Rock, Move::Rock),
-        "A Z" => return (Move::Rock, Move::Paper),
-        "B X" => return (Move::Paper, Move::Rock),
-        "B Y" => return (Move::Paper, Move::Paper),
-        "B Z" => return (Move::Paper, Move::Scissors),
-        "C X" => return (Move::Scissors, Move::Paper),
-        "C Y" => return (Move::Scissors, Move::Scissors),
-        "C Z" => return (Move::Scissors, Move::Rock),
+        "A X" => (Move::Rock, Move::Scissors),
+        "A Y" => (Move::Rock, Move::Rock),
+        "A Z" => (Move::Rock, Move::Paper),
+        "B X" => (Move::Paper, Move::Rock),
+        "B Y" => (Move::Paper, Move::Paper),
+        "B Z" => (Move::Paper, Move::Scissors),
+        "C X" => (Move::Scissors, Move::Paper),
+        "C Y" => (Move::Scissors, Move::Scissors),
+        "C Z" => (Move::Scissors, Move::Rock),
         _ => panic!("unexpected input")
     }
 }
@@ -53,12 +53,10 @@ fn calculate(file: &str) -> i32 {
     let mut acc = 0;
 
     let file = File::open(file).unwrap();
-    for line in io::BufReader::new(file).lines() {
-        if let Ok(line) = line {
-            let m = input(line.as_str());
-            let s = score(m);
-            acc += s;
-        }
+    for line in io::BufReader::new(file).lines().map(|l| l.unwrap()) {
+        let m = input(line.as_str());
+        let s = score(m);
+        acc += s;
     }
 
     acc
@@ -6,7 +6,7 @@ use std::{
 
 use itertools::Itertools;
 
-fn duplicate(s: &String) -> char {
+fn duplicate(s: &str) -> char {
     let mut items: HashSet<char> = HashSet::new();
 
     let n = s.len() / 2;
@@ -35,17 +35,15 @@ fn score(c: char) -> u32 {
         return v - a + 1;
     }
 
-    return v - big_a + 27;
+    v - big_a + 27
 }
 
 fn calculate(file: &str) -> u32 {
     let mut acc = 0;
 
     let file = File::open(file).unwrap();
-    for line in io::BufReader::new(file).lines() {
-        if let Ok(line) = line {
-            acc += score(duplicate(&line));
-        }
+    for line in io::BufReader::new(file).lines().map(|l| l.unwrap()) {
+        acc += score(duplicate(&line));
     }
 
     acc
@@ -78,14 +76,12 @@ fn calculate_part2(file: &str, group_size: usize) -> u32 {
 
     let mut group: Vec<String> = vec![];
 
-    for line in io::BufReader::new(file).lines() {
-        if let Ok(line) = line {
-            group.push(line);
+    for line in io::BufReader::new(file).lines().map(|l| l.unwrap()) {
+        group.push(line);
 
-            if group.len() == group_size {
-                acc += score(common(&group));
-                group.clear();
-            }
+        if group.len() == group_size {
+            acc += score(common(&group));
+            group.clear();
         }
     }
 
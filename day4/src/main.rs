@@ -11,8 +11,8 @@ struct Range {
 impl Range {
     fn new(start: u32, end: u32) -> Range {
         Range {
-            start: start,
-            end: end,
+            start,
+            end,
         }
     }
 
@@ -21,8 +21,7 @@ impl Range {
     }
 
     fn overlaps(&self, other: &Range) -> bool {
-        (other.end >= self.start && other.start <= self.start)
-            || (other.start <= self.end && other.end >= self.start)
+        other.end >= self.start && other.start <= self.end
     }
 }
 
@@ -32,7 +31,7 @@ fn parse(line: &str) -> Vec<Range> {
     for elf in line.split(",") {
         let range: Vec<&str> = elf.split("-").collect();
 
-        let start = range.get(0).unwrap().parse::<u32>().unwrap();
+        let start = range.first().unwrap().parse::<u32>().unwrap();
         let end = range.get(1).unwrap().parse::<u32>().unwrap();
 
         results.push(Range::new(start, end));
@@ -48,7 +47,7 @@ fn calculate(file: &str) -> u32 {
     for line in io::BufReader::new(file).lines() {
         let ranges = parse(line.unwrap().as_str());
 
-        let r1 = ranges.get(0).unwrap();
+        let r1 = ranges.first().unwrap();
         let r2 = ranges.get(1).unwrap();
 
         if r1.contains(r2) || r2.contains(r1) {
@@ -66,7 +65,7 @@ fn calculate_part2(file: &str) -> u32 {
     for line in io::BufReader::new(file).lines() {
         let ranges = parse(line.unwrap().as_str());
 
-        let r1 = ranges.get(0).unwrap();
+        let r1 = ranges.first().unwrap();
         let r2 = ranges.get(1).unwrap();
 
         if r1.overlaps(r2) {
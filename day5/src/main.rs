@@ -37,7 +37,7 @@ impl Stacks {
 
         stacks.iter_mut().for_each(|v| v.reverse());
 
-        Stacks { stacks: stacks }
+        Stacks { stacks }
     }
 
     fn move_crates(&mut self, from: usize, to: usize, count: u32) {
@@ -70,10 +70,7 @@ impl Stacks {
     fn result(&self) -> String {
         let mut s = String::new();
         for stack in &self.stacks {
-            match stack.last() {
-                Some(value) => s.push(*value),
-                None => (),
-            }
+            if let Some(value) = stack.last() { s.push(*value) }
         }
 
         s
@@ -109,9 +106,9 @@ struct Move {
 impl Move {
     fn new(count: u32, from: usize, to: usize) -> Move {
         Move {
-            count: count,
-            from: from,
-            to: to,
+            count,
+            from,
+            to,
         }
     }
 
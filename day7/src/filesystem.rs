@@ -0,0 +1,273 @@
+use std::fmt::Display;
+
+pub type NodeId = usize;
+
+pub enum NodeKind {
+    File,
+    Directory(Vec<NodeId>),
+}
+
+pub struct Node {
+    pub name: String,
+    pub parent: Option<NodeId>,
+    pub kind: NodeKind,
+    // for files this is the file size, for directories the total size of
+    // everything below them, kept up to date as files are added
+    pub size: usize,
+}
+
+impl Node {
+    pub fn is_dir(&self) -> bool {
+        matches!(self.kind, NodeKind::Directory(_))
+    }
+
+    pub fn children(&self) -> &[NodeId] {
+        match &self.kind {
+            NodeKind::Directory(children) => children,
+            NodeKind::File => &[],
+        }
+    }
+}
+
+pub struct FileSystem {
+    nodes: Vec<Node>,
+}
+
+impl Default for FileSystem {
+    fn default() -> Self {
+        FileSystem::new()
+    }
+}
+
+impl FileSystem {
+    pub const ROOT: NodeId = 0;
+
+    pub fn new() -> FileSystem {
+        FileSystem {
+            nodes: vec![Node {
+                name: "/".to_string(),
+                parent: None,
+                kind: NodeKind::Directory(vec![]),
+                size: 0,
+            }],
+        }
+    }
+
+    pub fn node(&self, id: NodeId) -> &Node {
+        &self.nodes[id]
+    }
+
+    pub fn size(&self, id: NodeId) -> usize {
+        self.nodes[id].size
+    }
+
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.nodes[id].parent
+    }
+
+    pub fn child(&self, id: NodeId, name: &str) -> Option<NodeId> {
+        self.nodes[id]
+            .children()
+            .iter()
+            .copied()
+            .find(|c| self.nodes[*c].name == name)
+    }
+
+    // adding a node that's already there gives the existing one, as long as
+    // it's the same kind and, for files, the same size
+    fn add_node(&mut self, parent: NodeId, name: &str, kind: NodeKind, size: usize) -> Result<NodeId, String> {
+        if let Some(existing) = self.child(parent, name) {
+            let node = &self.nodes[existing];
+
+            return match (&node.kind, &kind) {
+                (NodeKind::Directory(_), NodeKind::Directory(_)) => Ok(existing),
+                (NodeKind::File, NodeKind::File) if node.size == size => Ok(existing),
+                (NodeKind::File, NodeKind::File) => Err(format!(
+                    "{} is already a file of {} bytes, not {}",
+                    self.path(existing),
+                    node.size,
+                    size
+                )),
+                (NodeKind::File, _) => Err(format!("{} is already a file", self.path(existing))),
+                (NodeKind::Directory(_), _) => Err(format!("{} is already a directory", self.path(existing))),
+            };
+        }
+
+        let id = self.nodes.len();
+        match &mut self.nodes[parent].kind {
+            NodeKind::Directory(children) => children.push(id),
+            NodeKind::File => return Err(format!("{} is not a directory", self.path(parent))),
+        }
+
+        self.nodes.push(Node {
+            name: name.to_string(),
+            parent: Some(parent),
+            kind,
+            size,
+        });
+
+        let mut ancestor = Some(parent);
+        while let Some(a) = ancestor {
+            self.nodes[a].size += size;
+            ancestor = self.nodes[a].parent;
+        }
+
+        Ok(id)
+    }
+
+    pub fn add_directory(&mut self, parent: NodeId, name: &str) -> Result<NodeId, String> {
+        self.add_node(parent, name, NodeKind::Directory(vec![]), 0)
+    }
+
+    pub fn add_file(&mut self, parent: NodeId, name: &str, size: usize) -> Result<NodeId, String> {
+        self.add_node(parent, name, NodeKind::File, size)
+    }
+
+    // like resolve, but adds each directory on the way that isn't there yet
+    // the way mkdir -p does
+    pub fn add_directories(&mut self, from: NodeId, path: &str) -> Result<NodeId, String> {
+        let mut current = if path.starts_with('/') {
+            FileSystem::ROOT
+        } else {
+            from
+        };
+
+        for component in path.split('/').filter(|c| !c.is_empty()) {
+            current = match component {
+                "." => current,
+                ".." => self.parent(current).unwrap_or(FileSystem::ROOT),
+                name => self.add_directory(current, name)?,
+            };
+        }
+
+        Ok(current)
+    }
+
+    // resolves a path such as "/a/e/i", "e/i" or "../d" starting from the
+    // given directory, absolute paths always start from the root
+    pub fn resolve(&self, from: NodeId, path: &str) -> Option<NodeId> {
+        let mut current = if path.starts_with('/') {
+            FileSystem::ROOT
+        } else {
+            from
+        };
+
+        for component in path.split('/').filter(|c| !c.is_empty()) {
+            current = match component {
+                "." => current,
+                ".." => self.parent(current).unwrap_or(FileSystem::ROOT),
+                name => self.child(current, name)?,
+            };
+        }
+
+        Some(current)
+    }
+
+    pub fn path(&self, id: NodeId) -> String {
+        let mut names = vec![];
+
+        let mut current = id;
+        while let Some(parent) = self.parent(current) {
+            names.push(self.nodes[current].name.as_str());
+            current = parent;
+        }
+
+        names.reverse();
+
+        format!("/{}", names.join("/"))
+    }
+
+    // every node below and including start paired with its depth relative to
+    // start, parents always come before their children
+    pub fn walk(&self, start: NodeId) -> Vec<(NodeId, usize)> {
+        let mut result = vec![];
+
+        let mut stack = vec![(start, 0)];
+        while let Some((id, depth)) = stack.pop() {
+            result.push((id, depth));
+
+            for c in self.node(id).children().iter().rev() {
+                stack.push((*c, depth + 1));
+            }
+        }
+
+        result
+    }
+
+    pub fn directories(&self) -> impl Iterator<Item = NodeId> + '_ {
+        (0..self.nodes.len()).filter(|id| self.nodes[*id].is_dir())
+    }
+
+    fn fmt_impl(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        id: NodeId,
+        depth: usize,
+    ) -> std::fmt::Result {
+        let indent = " ".repeat(depth);
+        let node = self.node(id);
+
+        if !node.is_dir() {
+            return writeln!(f, "{}{}: {}", indent, node.name, node.size);
+        }
+
+        if id == FileSystem::ROOT {
+            writeln!(f, "{}/", indent)?;
+        } else {
+            writeln!(f, "{}{}/", indent, node.name)?;
+        }
+
+        for c in node.children() {
+            self.fmt_impl(f, *c, depth + 2)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for FileSystem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_impl(f, FileSystem::ROOT, 0)
+    }
+}
+
+#[test]
+fn test_lookup() {
+    let mut fs = FileSystem::new();
+    let a = fs.add_directory(FileSystem::ROOT, "a").unwrap();
+    let e = fs.add_directory(a, "e").unwrap();
+    let i = fs.add_file(e, "i", 584).unwrap();
+
+    assert_eq!(Some(i), fs.resolve(FileSystem::ROOT, "/a/e/i"));
+    assert_eq!(Some(e), fs.resolve(i, ".."));
+    assert_eq!(Some(a), fs.resolve(e, "../../a"));
+    assert_eq!(None, fs.resolve(FileSystem::ROOT, "/a/x"));
+    assert_eq!("/a/e/i", fs.path(i));
+    assert_eq!("/", fs.path(FileSystem::ROOT));
+}
+
+#[test]
+fn test_cached_sizes() {
+    let mut fs = FileSystem::new();
+    let a = fs.add_directory(FileSystem::ROOT, "a").unwrap();
+    fs.add_file(a, "f", 100).unwrap();
+    fs.add_file(FileSystem::ROOT, "b", 20).unwrap();
+
+    // listing the same file twice must not count it twice
+    fs.add_file(a, "f", 100).unwrap();
+
+    assert_eq!(100, fs.size(a));
+    assert_eq!(120, fs.size(FileSystem::ROOT));
+    assert_eq!(4, fs.walk(FileSystem::ROOT).len());
+    assert_eq!(2, fs.directories().count());
+
+    assert_eq!(
+        Err("/a/f is already a file of 100 bytes, not 200".to_string()),
+        fs.add_file(a, "f", 200)
+    );
+    assert_eq!(Err("/a/f is already a file".to_string()), fs.add_directory(a, "f"));
+    assert_eq!(Err("/a is already a directory".to_string()), fs.add_file(FileSystem::ROOT, "a", 5));
+    let f = fs.child(a, "f").unwrap();
+    assert_eq!(Err("/a/f is not a directory".to_string()), fs.add_file(f, "x", 1));
+    assert_eq!(120, fs.size(FileSystem::ROOT));
+}
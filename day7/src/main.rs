@@ -1,144 +1,272 @@
+mod filesystem;
+mod planner;
+mod query;
+mod transcript;
+
 use std::{
-    fmt::Display,
     fs::File,
     io::{BufRead, BufReader},
-    iter::Peekable,
+    path::Path,
+    time::Instant,
 };
 
-enum Node {
-    File((String, usize)),
-    Directory((String, Vec<Node>)),
+use common::cli::{input_and_command, next_value, parse_number};
+use filesystem::FileSystem;
+use planner::{Candidates, Objective};
+use query::{DuOptions, FindFilter, NodeType};
+use transcript::TraversalOrder;
+
+const USAGE: &str = "usage:
+  day7 [--input FILE] du [-h] [-sort] [-d DEPTH] [PATH]
+  day7 [--input FILE] tree [-h] [-d DEPTH] [PATH]
+  day7 [--input FILE] find [PATH] [-name GLOB] [-type f|d] [-minsize SIZE] [-maxsize SIZE]
+                           [-mindepth DEPTH] [-maxdepth DEPTH]
+  day7 [--input FILE] plan [-h] [-required SIZE] [-disk SIZE] [-minimise bytes|count]
+                           [-candidates dirs|files|all]
+  day7 transcript [-order dfs|bfs] DIR
+  day7 [--input FILE] bench [-order dfs|bfs] [-required SIZE] [-disk SIZE] [DIR]
+
+bench times a transcript of DIR when it's given, otherwise the input's";
+
+fn read_lines(file: &str) -> Result<Vec<String>, String> {
+    let f = File::open(file).map_err(|e| format!("{}: {}", file, e))?;
+
+    BufReader::new(f)
+        .lines()
+        .collect::<Result<Vec<String>, _>>()
+        .map_err(|e| format!("{}: {}", file, e))
+}
+
+fn parse(file: &str) -> Result<FileSystem, String> {
+    parse_lines(read_lines(file)?.into_iter()).map_err(|e| format!("{}: {}", file, e))
 }
 
-impl Node {
-    fn fmt_impl(&self, f: &mut std::fmt::Formatter<'_>, depth: usize) -> std::fmt::Result {
-        let indent = " ".repeat(depth);
-        match self {
-            Node::File((name, size)) => writeln!(f, "{}{}: {}", indent, name, size)?,
-            Node::Directory((name, children)) => {
-                writeln!(f, "{}{}/", indent, name)?;
-                for c in children {
-                    c.fmt_impl(f, depth + 2)?;
+// names are everything after the first space so they may contain spaces
+fn parse_lines(lines: impl Iterator<Item = String>) -> Result<FileSystem, String> {
+    let mut fs = FileSystem::new();
+    let mut cwd = FileSystem::ROOT;
+
+    for (i, line) in lines.enumerate() {
+        let error = |e: String| format!("line {}: {}", i + 1, e);
+
+        if let Some(d) = line.strip_prefix("$ cd ") {
+            cwd = match fs.resolve(cwd, d) {
+                Some(dir) if !fs.node(dir).is_dir() => {
+                    return Err(error(format!("cd: {} is not a directory", fs.path(dir))))
                 }
-            }
+                Some(dir) => dir,
+                None => fs.add_directories(cwd, d).map_err(|e| error(format!("cd: {}", e)))?,
+            };
+        } else if line == "$ ls" {
+            // the listing follows on the next lines
+        } else if let Some(name) = line.strip_prefix("dir ") {
+            fs.add_directory(cwd, name).map_err(error)?;
+        } else if let Some((size, name)) = line.split_once(' ') {
+            let size = size
+                .parse::<usize>()
+                .map_err(|_| error(format!("invalid size: {}", size)))?;
+            fs.add_file(cwd, name, size).map_err(error)?;
+        } else {
+            return Err(error(format!("unexpected line: {}", line)));
         }
-
-        Ok(())
     }
 
-    fn directories(&self) -> (Vec<(String, usize)>, usize) {
+    Ok(fs)
+}
 
-        if let Node::Directory((name, children)) = self {
+fn small_directories(fs: &FileSystem, max: usize) -> usize {
+    fs.directories().map(|d| fs.size(d)).filter(|s| *s <= max).sum()
+}
 
-            let mut my_size: usize = 0;
-            let mut v: Vec<(String, usize)> = vec![];
+// the smallest directory that frees up enough space, if any does
+fn directory_to_delete(fs: &FileSystem, required: usize, disk_size: usize) -> Option<usize> {
+    let used = fs.size(FileSystem::ROOT);
+    let to_free = required.saturating_sub(disk_size.saturating_sub(used));
 
-            for c in children {
-                match c {
-                    Node::Directory(_) => {
-                        let (children, total) = c.directories();
+    fs.directories().map(|d| fs.size(d)).filter(|s| *s >= to_free).min()
+}
 
-                        v.extend(children);
-                        my_size += total;
-                    },
-                    Node::File((_name, size)) => {
-                        my_size += size;
-                    }
-                }
-            }
+fn calculate(file: &str, max: usize) -> usize {
+    small_directories(&parse(file).unwrap(), max)
+}
 
-            v.push((name.clone(), my_size));
+fn calculate_part2(file: &str, required: usize, disk_size: usize) -> usize {
+    let fs = parse(file).unwrap();
 
-            return (v, my_size);
-        }
+    println!("tree: {}", fs);
 
-        unreachable!();
-    }
-}
+    let used = fs.size(FileSystem::ROOT);
+    println!("used: {}, required: {}, disk_size {}", used, required, disk_size);
 
-impl Display for Node {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.fmt_impl(f, 0)
-    }
+    directory_to_delete(&fs, required, disk_size).unwrap()
 }
 
-fn parse(file: &str) -> Node {
-    let file = File::open(file).unwrap();
-    let mut iter = BufReader::new(file).lines().map(|l| l.unwrap()).peekable();
-
-    // consume a line, we know it start "cd /"
-    iter.next();
+// Times reading a transcript into a file system and both parts on it. The
+// planner is left out, its tables grow with the bytes to free.
+fn bench(lines: Vec<String>, required: usize, disk_size: usize) -> Result<(), String> {
+    let bytes = lines.iter().map(|l| l.len() + 1).sum::<usize>();
+    println!("lines: {}, text: {} bytes", lines.len(), bytes);
+
+    let start = Instant::now();
+    let fs = parse_lines(lines.into_iter())?;
+    println!(
+        "parse: {:?} ({} directories, {} bytes used)",
+        start.elapsed(),
+        fs.directories().count(),
+        fs.size(FileSystem::ROOT)
+    );
+
+    let start = Instant::now();
+    let result = small_directories(&fs, 100000);
+    println!("part 1: {:?} ({})", start.elapsed(), result);
+
+    let start = Instant::now();
+    let result = directory_to_delete(&fs, required, disk_size);
+    println!("part 2: {:?} ({:?})", start.elapsed(), result);
+
+    Ok(())
+}
 
-    parse_directory(&mut iter, "/")
+fn parse_size(value: &str) -> Result<usize, String> {
+    query::parse_size(value).ok_or(format!("invalid size: {}", value))
 }
 
-fn parse_directory<T: Iterator<Item = String>>(lines: &mut Peekable<T>, name: &str) -> Node {
-    let mut children: Vec<Node> = vec![];
-
-    while let Some(line) = lines.next() {
-        if line.starts_with("$ cd ..") {
-            break;
-        } else if line.starts_with("$ cd") {
-            let tokens = line.split(" ").collect::<Vec<&str>>();
-            let d = tokens.get(2).unwrap();
-            children.push(parse_directory(lines, d));
-        } else if line.starts_with("$ ls") {
-            while lines.peek().is_some() {
-                if lines.peek().unwrap().starts_with("$") {
-                    break;
+fn run_command(args: &[String]) -> Result<(), String> {
+    let mut args = args.iter();
+    let (input, command) = input_and_command(&mut args)?;
+
+    let mut path = "/";
+    let mut human = false;
+    let mut sort = false;
+    let mut max_depth = None;
+    let mut filter = FindFilter::default();
+    let mut required = 30000000;
+    let mut disk_size = 70000000;
+    let mut objective = Objective::Bytes;
+    let mut candidates = Candidates::Directories;
+    let mut order = TraversalOrder::DepthFirst;
+    let mut dir = None;
+
+    while let Some(arg) = args.next() {
+        match (command.as_str(), arg.as_str()) {
+            ("du" | "tree" | "plan", "-h") => human = true,
+            ("du", "-sort") => sort = true,
+            ("du" | "tree", "-d") => max_depth = Some(parse_number(next_value(&mut args, arg)?)?),
+            ("find", "-name") => filter.name = Some(next_value(&mut args, arg)?.clone()),
+            ("find", "-type") => {
+                filter.node_type = match next_value(&mut args, arg)?.as_str() {
+                    "f" => Some(NodeType::File),
+                    "d" => Some(NodeType::Directory),
+                    t => return Err(format!("unknown type: {}", t)),
                 }
-
-                let line = lines.next().unwrap();
-
-                if line.starts_with("dir") {
-                    // do nothing
-                } else {
-                    let tokens = line.split(" ").collect::<Vec<&str>>();
-                    let size = tokens.get(0).unwrap().parse::<usize>().unwrap();
-                    let name = tokens.get(1).unwrap();
-
-                    children.push(Node::File((name.to_string(), size)));
+            }
+            ("find", "-minsize") => filter.min_size = Some(parse_size(next_value(&mut args, arg)?)?),
+            ("find", "-maxsize") => filter.max_size = Some(parse_size(next_value(&mut args, arg)?)?),
+            ("find", "-mindepth") => filter.min_depth = Some(parse_number(next_value(&mut args, arg)?)?),
+            ("find", "-maxdepth") => filter.max_depth = Some(parse_number(next_value(&mut args, arg)?)?),
+            ("plan" | "bench", "-required") => required = parse_size(next_value(&mut args, arg)?)?,
+            ("plan" | "bench", "-disk") => disk_size = parse_size(next_value(&mut args, arg)?)?,
+            ("plan", "-minimise") => {
+                objective = match next_value(&mut args, arg)?.as_str() {
+                    "bytes" => Objective::Bytes,
+                    "count" => Objective::Count,
+                    o => return Err(format!("unknown objective: {}", o)),
+                }
+            }
+            ("plan", "-candidates") => {
+                candidates = match next_value(&mut args, arg)?.as_str() {
+                    "dirs" => Candidates::Directories,
+                    "files" => Candidates::Files,
+                    "all" => Candidates::All,
+                    c => return Err(format!("unknown candidates: {}", c)),
                 }
             }
+            ("transcript" | "bench", "-order") => {
+                order = match next_value(&mut args, arg)?.as_str() {
+                    "dfs" => TraversalOrder::DepthFirst,
+                    "bfs" => TraversalOrder::BreadthFirst,
+                    o => return Err(format!("unknown order: {}", o)),
+                }
+            }
+            ("transcript" | "bench", a) if !a.starts_with('-') => dir = Some(a),
+            (_, a) if !a.starts_with('-') => path = a,
+            (_, a) => return Err(format!("unknown option for {}: {}", command, a)),
         }
     }
 
-    Node::Directory((name.to_string(), children))
-}
+    if command == "transcript" {
+        let dir = dir.ok_or("transcript needs a directory")?;
+        let lines = transcript::transcript(Path::new(dir), order).map_err(|e| format!("{}: {}", dir, e))?;
 
-fn calculate(file: &str, max: usize) -> usize {
-    let tree = parse(file);
-
-    let d = tree.directories();
-
-    d.0.iter().filter(|d| d.1 <= max).map(|d| d.1).sum()
-}
-
-fn calculate_part2(file: &str, required: usize, disk_size: usize) -> usize {
-    let tree = parse(file);
-
-    println!("tree: {}", tree);
+        for line in lines {
+            println!("{}", line);
+        }
 
-    let d = tree.directories();
+        return Ok(());
+    }
 
-    let used = d.1;
-    println!("used: {}, required: {}, disk_size {}", used, required, disk_size);
-    let to_free = required - (disk_size - used);
+    if command == "bench" {
+        let lines = match dir {
+            Some(dir) => {
+                let start = Instant::now();
+                let lines = transcript::transcript(Path::new(dir), order).map_err(|e| format!("{}: {}", dir, e))?;
+                println!("transcript: {:?}", start.elapsed());
+                lines
+            }
+            None => read_lines(input)?,
+        };
 
-    let mut to_delete = disk_size;
+        return bench(lines, required, disk_size);
+    }
 
-    for dir in d.0 {
-        if dir.1 >= to_free && dir.1 < to_delete {
-            to_delete = dir.1;
+    let fs = parse(input)?;
+    let start = fs
+        .resolve(FileSystem::ROOT, path)
+        .ok_or(format!("no such file or directory: {}", path))?;
+
+    let lines = match command.as_str() {
+        "du" => query::du(&fs, start, &DuOptions { human, max_depth, sort }),
+        "tree" => query::tree(&fs, start, human, max_depth),
+        "find" => query::find(&fs, start, &filter)
+            .iter()
+            .map(|id| fs.path(*id))
+            .collect(),
+        "plan" => {
+            let used = fs.size(FileSystem::ROOT);
+            let to_free = required.saturating_sub(disk_size.saturating_sub(used));
+
+            let plan = planner::plan(&fs, to_free, objective, candidates)
+                .ok_or(format!("cannot free {} bytes", to_free))?;
+
+            let mut lines = plan
+                .nodes
+                .iter()
+                .map(|id| format!("{}\t{}", query::format_size(fs.size(*id), human), fs.path(*id)))
+                .collect::<Vec<String>>();
+            lines.push(format!(
+                "{}\ttotal ({} deletions, {} required)",
+                query::format_size(plan.freed, human),
+                plan.nodes.len(),
+                query::format_size(to_free, human)
+            ));
+
+            lines
         }
+        c => return Err(format!("unknown command: {}", c)),
+    };
+
+    for line in lines {
+        println!("{}", line);
     }
 
-    to_delete
+    Ok(())
 }
 
 fn main() {
-    println!("result: {}", calculate("input/problem.txt", 100000));
-    println!("result part 2: {}", calculate_part2("input/problem.txt", 30000000, 70000000));
+    common::cli::main(USAGE, run_command, || {
+        println!("result: {}", calculate("input/problem.txt", 100000));
+        println!("result part 2: {}", calculate_part2("input/problem.txt", 30000000, 70000000));
+    });
 }
 
 #[test]
@@ -150,3 +278,88 @@ fn test_example() {
 fn test_example_part2() {
     assert_eq!(24933642, calculate_part2("input/example.txt", 30000000, 70000000));
 }
+
+#[test]
+fn test_parse_paths() {
+    let fs = parse("input/example.txt").unwrap();
+
+    let e = fs.resolve(FileSystem::ROOT, "/a/e").unwrap();
+    assert_eq!(584, fs.size(e));
+    assert_eq!("/a/e", fs.path(e));
+    assert_eq!(fs.resolve(FileSystem::ROOT, "/a"), fs.parent(e));
+    assert_eq!(24933642, fs.size(fs.resolve(FileSystem::ROOT, "/d").unwrap()));
+
+    let lines = |lines: &[&str]| parse_lines(lines.iter().map(|l| l.to_string())).err();
+    assert_eq!(
+        Some("line 4: cd: /b.txt is not a directory".to_string()),
+        lines(&["$ cd /", "$ ls", "14848514 b.txt", "$ cd b.txt"])
+    );
+    assert_eq!(
+        Some("line 3: /b.txt is already a file of 14848514 bytes, not 5".to_string()),
+        lines(&["$ ls", "14848514 b.txt", "5 b.txt"])
+    );
+    assert_eq!(Some("line 1: unexpected line: $".to_string()), lines(&["$"]));
+
+    let binary = std::env::temp_dir().join(format!("day7-{}.bin", std::process::id()));
+    std::fs::write(&binary, [b'$', b' ', 0xff]).unwrap();
+    let error = parse(binary.to_str().unwrap()).err();
+    std::fs::remove_file(&binary).unwrap();
+    assert_eq!(
+        Some(format!("{}: stream did not contain valid UTF-8", binary.display())),
+        error
+    );
+    assert_eq!(
+        Some("line 4: cd: /b.txt is already a file".to_string()),
+        lines(&["$ cd /", "$ ls", "14848514 b.txt", "$ cd b.txt/x"])
+    );
+
+    // each directory along a path that isn't there yet is added
+    let fs = parse_lines(["$ cd x/y", "$ ls", "5 f", "$ cd /x/z/../y"].iter().map(|l| l.to_string())).unwrap();
+    let y = fs.resolve(FileSystem::ROOT, "/x/y").unwrap();
+    assert_eq!("/x/y", fs.path(y));
+    assert_eq!(Some(5), fs.resolve(FileSystem::ROOT, "/x").map(|x| fs.size(x)));
+    assert!(fs.resolve(FileSystem::ROOT, "/x/z").is_some());
+}
+
+#[test]
+fn test_transcript_round_trip() {
+    use std::fs;
+
+    fn disk_usage(dir: &Path, sizes: &mut Vec<(String, usize)>, path: &str) -> usize {
+        let mut total = 0;
+        for entry in fs::read_dir(dir).unwrap().map(|e| e.unwrap()) {
+            let metadata = entry.metadata().unwrap();
+            let name = entry.file_name().into_string().unwrap();
+
+            if metadata.is_dir() {
+                total += disk_usage(&entry.path(), sizes, &format!("{}/{}", path, name));
+            } else {
+                total += metadata.len() as usize;
+            }
+        }
+
+        sizes.push((if path.is_empty() { "/".to_string() } else { path.to_string() }, total));
+        total
+    }
+
+    let temp = transcript::TempDir::new("round-trip");
+    let root = temp.0.clone();
+    for (i, dir) in ["a/e", "a/f g", "d", "d/h/i/j", "k"].iter().enumerate() {
+        fs::create_dir_all(root.join(dir)).unwrap();
+        fs::write(root.join(dir).join(format!("file {}.txt", i)), vec![b'x'; 100 * i + 7]).unwrap();
+    }
+    fs::write(root.join("top"), vec![b'x'; 12345]).unwrap();
+
+    let mut expected = vec![];
+    disk_usage(&root, &mut expected, "");
+
+    for order in [TraversalOrder::DepthFirst, TraversalOrder::BreadthFirst] {
+        let lines = transcript::transcript(&root, order).unwrap();
+        let fs = parse_lines(lines.into_iter()).unwrap();
+
+        assert_eq!(expected.len(), fs.directories().count());
+        for (path, size) in expected.iter() {
+            assert_eq!(*size, fs.size(fs.resolve(FileSystem::ROOT, path).unwrap()), "{}", path);
+        }
+    }
+}
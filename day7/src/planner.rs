@@ -0,0 +1,397 @@
+use crate::filesystem::{FileSystem, NodeId};
+
+#[derive(Clone, Copy)]
+pub enum Objective {
+    // delete as few bytes as possible
+    Bytes,
+    // delete as few nodes as possible, then as few bytes as possible
+    Count,
+}
+
+#[derive(Clone, Copy)]
+pub enum Candidates {
+    Directories,
+    Files,
+    All,
+}
+
+pub struct Plan {
+    pub nodes: Vec<NodeId>,
+    pub freed: usize,
+}
+
+#[derive(Clone)]
+struct Bitset {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl Bitset {
+    fn new(len: usize) -> Bitset {
+        Bitset {
+            words: vec![0; len.div_ceil(64)],
+            len,
+        }
+    }
+
+    fn contains(&self, i: usize) -> bool {
+        i < self.len && self.words[i / 64] & (1 << (i % 64)) != 0
+    }
+
+    fn insert(&mut self, i: usize) {
+        self.words[i / 64] |= 1 << (i % 64);
+    }
+
+    // self |= other << shift, dropping anything that ends up past len
+    fn union_shifted(&mut self, other: &Bitset, shift: usize) {
+        let (word_shift, bit_shift) = (shift / 64, shift % 64);
+
+        for i in (word_shift..self.words.len()).rev() {
+            let j = i - word_shift;
+
+            let mut w = other.words[j] << bit_shift;
+            if bit_shift > 0 && j > 0 {
+                w |= other.words[j - 1] >> (64 - bit_shift);
+            }
+
+            self.words[i] |= w;
+        }
+
+        if !self.len.is_multiple_of(64) {
+            let last = self.words.len() - 1;
+            self.words[last] &= (1 << (self.len % 64)) - 1;
+        }
+    }
+
+    fn first_from(&self, from: usize) -> Option<usize> {
+        if from >= self.len {
+            return None;
+        }
+
+        let mut i = from / 64;
+        let mut w = self.words[i] & (u64::MAX << (from % 64));
+        loop {
+            if w != 0 {
+                return Some(i * 64 + w.trailing_zeros() as usize);
+            }
+
+            i += 1;
+            if i == self.words.len() {
+                return None;
+            }
+            w = self.words[i];
+        }
+    }
+}
+
+// Everything that can be freed using the nodes from a position in pre-order
+// onwards. Totals below to_free are kept exactly in a bitset, of the totals
+// reaching to_free only the smallest matters.
+struct Layer {
+    slots: Vec<usize>,
+    sets: Vec<Bitset>,
+    over: Vec<Option<usize>>,
+}
+
+impl Layer {
+    fn reachable(&self, position: usize, freed: usize) -> bool {
+        self.sets[self.slots[position]].contains(freed) || self.over[position] == Some(freed)
+    }
+}
+
+struct Planner<'a> {
+    fs: &'a FileSystem,
+    order: Vec<NodeId>,
+    subtree_end: Vec<usize>,
+    candidates: Candidates,
+    to_free: usize,
+}
+
+impl Planner<'_> {
+    fn is_candidate(&self, position: usize) -> bool {
+        is_candidate(self.fs, self.order[position], self.candidates)
+    }
+
+    // the layer where nothing can be deleted
+    fn empty(&self) -> Layer {
+        let mut nothing = Bitset::new(self.to_free);
+        nothing.insert(0);
+
+        Layer {
+            slots: vec![0; self.order.len() + 1],
+            sets: vec![nothing],
+            over: vec![None; self.order.len() + 1],
+        }
+    }
+
+    // previous is the layer allowing one deletion fewer, without one any
+    // number of deletions is allowed
+    fn build(&self, previous: Option<&Layer>) -> Layer {
+        let mut layer = self.empty();
+
+        for i in (0..self.order.len()).rev() {
+            layer.slots[i] = layer.slots[i + 1];
+            layer.over[i] = layer.over[i + 1];
+
+            if !self.is_candidate(i) {
+                continue;
+            }
+
+            let size = self.fs.size(self.order[i]);
+            let end = self.subtree_end[i];
+            let (from_set, from_over) = match previous {
+                Some(p) => (&p.sets[p.slots[end]], p.over[end]),
+                None => (&layer.sets[layer.slots[end]], layer.over[end]),
+            };
+
+            let mut set = layer.sets[layer.slots[i + 1]].clone();
+            if size < self.to_free {
+                set.union_shifted(from_set, size);
+            }
+
+            let crossing = from_set
+                .first_from(self.to_free.saturating_sub(size))
+                .map(|f| f + size);
+
+            layer.over[i] = [layer.over[i], crossing, from_over.map(|f| f + size)]
+                .into_iter()
+                .flatten()
+                .min();
+
+            layer.sets.push(set);
+            layer.slots[i] = layer.sets.len() - 1;
+        }
+
+        layer
+    }
+
+    // walks the layers back from the best total to find the nodes behind it,
+    // layers[k] allows k deletions or all of them when there is only one
+    fn reconstruct(&self, layers: &[Layer], freed: usize) -> Vec<NodeId> {
+        let mut nodes = vec![];
+
+        let mut k = layers.len() - 1;
+        let mut remaining = freed;
+        let mut i = 0;
+
+        while remaining > 0 {
+            if !self.is_candidate(i) || layers[k].reachable(i + 1, remaining) {
+                i += 1;
+                continue;
+            }
+
+            nodes.push(self.order[i]);
+            remaining -= self.fs.size(self.order[i]);
+            i = self.subtree_end[i];
+
+            if layers.len() > 1 {
+                k -= 1;
+            }
+        }
+
+        nodes
+    }
+
+    // the most that could ever be freed, deleting every top level candidate
+    fn max_freed(&self) -> usize {
+        let mut best = vec![0; self.order.len() + 1];
+
+        for i in (0..self.order.len()).rev() {
+            let mut children = 0;
+            let mut c = i + 1;
+            while c < self.subtree_end[i] {
+                children += best[c];
+                c = self.subtree_end[c];
+            }
+
+            best[i] = if self.is_candidate(i) {
+                self.fs.size(self.order[i])
+            } else {
+                children
+            };
+        }
+
+        best[0]
+    }
+}
+
+fn is_candidate(fs: &FileSystem, id: NodeId, candidates: Candidates) -> bool {
+    if id == FileSystem::ROOT {
+        return false;
+    }
+
+    match candidates {
+        Candidates::Directories => fs.node(id).is_dir(),
+        Candidates::Files => !fs.node(id).is_dir(),
+        Candidates::All => true,
+    }
+}
+
+// Picks the set of nodes to delete so that at least to_free bytes are freed.
+//
+// This is a knapsack over the nodes in pre-order where taking a node skips
+// over its whole subtree, so a plan never contains a node and one of its
+// descendants. Minimising the count builds one layer per extra deletion
+// allowed until to_free can be reached.
+pub fn plan(fs: &FileSystem, to_free: usize, objective: Objective, candidates: Candidates) -> Option<Plan> {
+    if to_free == 0 {
+        return Some(Plan {
+            nodes: vec![],
+            freed: 0,
+        });
+    }
+
+    let walk = fs.walk(FileSystem::ROOT);
+
+    // subtree_end[i] is the position of the first node after i's subtree
+    let mut subtree_end = vec![walk.len(); walk.len()];
+    let mut open: Vec<usize> = vec![];
+    for (i, (_, depth)) in walk.iter().enumerate() {
+        while let Some(&j) = open.last() {
+            if walk[j].1 < *depth {
+                break;
+            }
+            subtree_end[j] = i;
+            open.pop();
+        }
+        open.push(i);
+    }
+
+    let planner = Planner {
+        fs,
+        order: walk.iter().map(|(id, _)| *id).collect(),
+        subtree_end,
+        candidates,
+        to_free,
+    };
+
+    if planner.max_freed() < to_free {
+        return None;
+    }
+
+    let layers = match objective {
+        Objective::Bytes => vec![planner.build(None)],
+        Objective::Count => {
+            let mut layers = vec![planner.empty()];
+
+            while layers.last().unwrap().over[0].is_none() {
+                let next = planner.build(layers.last());
+                layers.push(next);
+            }
+
+            layers
+        }
+    };
+
+    let freed = layers.last().unwrap().over[0]?;
+
+    Some(Plan {
+        nodes: planner.reconstruct(&layers, freed),
+        freed,
+    })
+}
+
+#[cfg(test)]
+fn example() -> FileSystem {
+    let mut fs = FileSystem::new();
+    let a = fs.add_directory(FileSystem::ROOT, "a").unwrap();
+    let e = fs.add_directory(a, "e").unwrap();
+    fs.add_file(e, "i", 584).unwrap();
+    fs.add_file(a, "f", 29116).unwrap();
+    fs.add_file(a, "g", 2557).unwrap();
+    fs.add_file(a, "h.lst", 62596).unwrap();
+    fs.add_file(FileSystem::ROOT, "b.txt", 14848514).unwrap();
+    fs.add_file(FileSystem::ROOT, "c.dat", 8504156).unwrap();
+    let d = fs.add_directory(FileSystem::ROOT, "d").unwrap();
+    fs.add_file(d, "j", 4060174).unwrap();
+    fs.add_file(d, "d.log", 8033020).unwrap();
+    fs.add_file(d, "d.ext", 5626152).unwrap();
+    fs.add_file(d, "k", 7214296).unwrap();
+
+    fs
+}
+
+#[cfg(test)]
+fn brute_force(fs: &FileSystem, to_free: usize, candidates: Candidates) -> Vec<(usize, usize)> {
+    let nodes = fs
+        .walk(FileSystem::ROOT)
+        .into_iter()
+        .map(|(id, _)| id)
+        .filter(|id| is_candidate(fs, *id, candidates))
+        .collect::<Vec<NodeId>>();
+
+    let is_ancestor = |a: NodeId, b: NodeId| {
+        let mut current = fs.parent(b);
+        while let Some(p) = current {
+            if p == a {
+                return true;
+            }
+            current = fs.parent(p);
+        }
+        false
+    };
+
+    let mut plans = vec![];
+    for mask in 0..(1u32 << nodes.len()) {
+        let chosen = (0..nodes.len())
+            .filter(|i| mask & (1 << i) != 0)
+            .map(|i| nodes[i])
+            .collect::<Vec<NodeId>>();
+
+        let nested = chosen
+            .iter()
+            .any(|a| chosen.iter().any(|b| is_ancestor(*a, *b)));
+        let freed = chosen.iter().map(|id| fs.size(*id)).sum::<usize>();
+
+        if !nested && freed >= to_free {
+            plans.push((freed, chosen.len()));
+        }
+    }
+
+    plans
+}
+
+#[test]
+fn test_plan_matches_brute_force() {
+    let fs = example();
+
+    for to_free in [1, 600, 32000, 95000, 8381165, 48381166] {
+        for candidates in [Candidates::Directories, Candidates::Files, Candidates::All] {
+            let plans = brute_force(&fs, to_free, candidates);
+
+            for objective in [Objective::Bytes, Objective::Count] {
+                let expected = match objective {
+                    Objective::Bytes => plans.iter().map(|(freed, count)| (*freed, *count)).min(),
+                    Objective::Count => plans.iter().map(|(freed, count)| (*count, *freed)).min(),
+                };
+
+                let plan = match plan(&fs, to_free, objective, candidates) {
+                    Some(plan) => plan,
+                    None => {
+                        assert_eq!(None, expected);
+                        continue;
+                    }
+                };
+
+                assert!(plan.freed >= to_free);
+                assert_eq!(plan.freed, plan.nodes.iter().map(|id| fs.size(*id)).sum::<usize>());
+
+                let (first, second) = expected.unwrap();
+                match objective {
+                    Objective::Bytes => assert_eq!(first, plan.freed),
+                    Objective::Count => assert_eq!((first, second), (plan.nodes.len(), plan.freed)),
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_plan_example() {
+    let fs = example();
+
+    let plan = plan(&fs, 32000, Objective::Bytes, Candidates::Files).unwrap();
+    let paths = plan.nodes.iter().map(|id| fs.path(*id)).collect::<Vec<String>>();
+    assert_eq!(vec!["/a/e/i", "/a/f", "/a/g"], paths);
+    assert_eq!(32257, plan.freed);
+}
@@ -0,0 +1,241 @@
+use crate::filesystem::{FileSystem, NodeId};
+
+pub fn human_size(size: usize) -> String {
+    const UNITS: [&str; 5] = ["", "K", "M", "G", "T"];
+
+    let mut value = size as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        size.to_string()
+    } else if value < 10.0 {
+        format!("{:.1}{}", value, UNITS[unit])
+    } else {
+        format!("{:.0}{}", value, UNITS[unit])
+    }
+}
+
+// the inverse of human_size, accepts plain byte counts or a K/M/G/T suffix
+pub fn parse_size(s: &str) -> Option<usize> {
+    let (digits, multiplier) = match s.chars().last()?.to_ascii_uppercase() {
+        'K' => (&s[..s.len() - 1], 1 << 10),
+        'M' => (&s[..s.len() - 1], 1 << 20),
+        'G' => (&s[..s.len() - 1], 1 << 30),
+        'T' => (&s[..s.len() - 1], 1 << 40),
+        _ => (s, 1),
+    };
+
+    digits.parse::<usize>().ok()?.checked_mul(multiplier)
+}
+
+pub fn format_size(size: usize, human: bool) -> String {
+    if human {
+        human_size(size)
+    } else {
+        size.to_string()
+    }
+}
+
+// matches a name against a shell style glob supporting * and ?
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<char>>();
+    let name = name.chars().collect::<Vec<char>>();
+
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, n));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            // let the last * swallow one more character and try again
+            p = star + 1;
+            n = matched + 1;
+            backtrack = Some((star, n));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[derive(Default)]
+pub struct DuOptions {
+    pub human: bool,
+    pub max_depth: Option<usize>,
+    pub sort: bool,
+}
+
+pub fn du(fs: &FileSystem, start: NodeId, options: &DuOptions) -> Vec<String> {
+    let mut entries = fs
+        .walk(start)
+        .into_iter()
+        .filter(|(id, depth)| {
+            fs.node(*id).is_dir() && options.max_depth.is_none_or(|max| *depth <= max)
+        })
+        .map(|(id, _)| id)
+        .collect::<Vec<NodeId>>();
+
+    if options.sort {
+        // largest first, ties broken by path so the output is stable
+        entries.sort_by_key(|id| (std::cmp::Reverse(fs.size(*id)), fs.path(*id)));
+    }
+
+    entries
+        .iter()
+        .map(|id| format!("{}\t{}", format_size(fs.size(*id), options.human), fs.path(*id)))
+        .collect()
+}
+
+pub fn tree(fs: &FileSystem, start: NodeId, human: bool, max_depth: Option<usize>) -> Vec<String> {
+    let mut lines = vec![];
+
+    let root = fs.node(start);
+    let name = if start == FileSystem::ROOT {
+        "/".to_string()
+    } else if root.is_dir() {
+        format!("{}/", root.name)
+    } else {
+        root.name.clone()
+    };
+    lines.push(format!("{} ({})", name, format_size(root.size, human)));
+
+    tree_impl(fs, start, human, max_depth, 1, "", &mut lines);
+
+    lines
+}
+
+fn tree_impl(
+    fs: &FileSystem,
+    id: NodeId,
+    human: bool,
+    max_depth: Option<usize>,
+    depth: usize,
+    prefix: &str,
+    lines: &mut Vec<String>,
+) {
+    if max_depth.is_some_and(|max| depth > max) {
+        return;
+    }
+
+    let children = fs.node(id).children();
+    for (i, c) in children.iter().enumerate() {
+        let last = i + 1 == children.len();
+        let node = fs.node(*c);
+
+        let suffix = if node.is_dir() { "/" } else { "" };
+        lines.push(format!(
+            "{}{}{}{} ({})",
+            prefix,
+            if last { "└── " } else { "├── " },
+            node.name,
+            suffix,
+            format_size(node.size, human)
+        ));
+
+        if node.is_dir() {
+            let prefix = format!("{}{}", prefix, if last { "    " } else { "│   " });
+            tree_impl(fs, *c, human, max_depth, depth + 1, &prefix, lines);
+        }
+    }
+}
+
+pub enum NodeType {
+    File,
+    Directory,
+}
+
+#[derive(Default)]
+pub struct FindFilter {
+    pub name: Option<String>,
+    pub node_type: Option<NodeType>,
+    pub min_size: Option<usize>,
+    pub max_size: Option<usize>,
+    pub min_depth: Option<usize>,
+    pub max_depth: Option<usize>,
+}
+
+impl FindFilter {
+    fn matches(&self, fs: &FileSystem, id: NodeId, depth: usize) -> bool {
+        let node = fs.node(id);
+
+        let type_matches = match self.node_type {
+            Some(NodeType::File) => !node.is_dir(),
+            Some(NodeType::Directory) => node.is_dir(),
+            None => true,
+        };
+
+        type_matches
+            && self.name.as_ref().is_none_or(|n| glob_match(n, &node.name))
+            && self.min_size.is_none_or(|min| node.size >= min)
+            && self.max_size.is_none_or(|max| node.size <= max)
+            && self.min_depth.is_none_or(|min| depth >= min)
+            && self.max_depth.is_none_or(|max| depth <= max)
+    }
+}
+
+pub fn find(fs: &FileSystem, start: NodeId, filter: &FindFilter) -> Vec<NodeId> {
+    fs.walk(start)
+        .into_iter()
+        .filter(|(id, depth)| filter.matches(fs, *id, *depth))
+        .map(|(id, _)| id)
+        .collect()
+}
+
+#[test]
+fn test_glob_match() {
+    assert!(glob_match("*.txt", "b.txt"));
+    assert!(glob_match("d.*", "d.log"));
+    assert!(glob_match("?", "k"));
+    assert!(glob_match("*a*b*", "xaybz"));
+    assert!(!glob_match("*.txt", "c.dat"));
+    assert!(!glob_match("?", "ab"));
+}
+
+#[test]
+fn test_sizes() {
+    assert_eq!("584", human_size(584));
+    assert_eq!("1.5K", human_size(1536));
+    assert_eq!("46M", human_size(48381165));
+    assert_eq!(Some(100 * 1024), parse_size("100k"));
+    assert_eq!(Some(48381165), parse_size("48381165"));
+    assert_eq!(None, parse_size("12x"));
+}
+
+#[test]
+fn test_du_and_find() {
+    let mut fs = FileSystem::new();
+    let a = fs.add_directory(FileSystem::ROOT, "a").unwrap();
+    let e = fs.add_directory(a, "e").unwrap();
+    fs.add_file(e, "i", 584).unwrap();
+    fs.add_file(a, "h.lst", 62596).unwrap();
+    let d = fs.add_directory(FileSystem::ROOT, "d").unwrap();
+    fs.add_file(d, "d.log", 8033020).unwrap();
+
+    let options = DuOptions {
+        sort: true,
+        max_depth: Some(1),
+        ..Default::default()
+    };
+    assert_eq!(
+        vec!["8096200\t/", "8033020\t/d", "63180\t/a"],
+        du(&fs, FileSystem::ROOT, &options)
+    );
+
+    let filter = FindFilter {
+        name: Some("*.*".to_string()),
+        min_size: Some(100000),
+        ..Default::default()
+    };
+    let found = find(&fs, FileSystem::ROOT, &filter);
+    assert_eq!(vec!["/d/d.log"], found.iter().map(|id| fs.path(*id)).collect::<Vec<_>>());
+}
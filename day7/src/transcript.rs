@@ -0,0 +1,174 @@
+use std::{
+    collections::VecDeque,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+#[derive(Clone, Copy)]
+pub enum TraversalOrder {
+    // list a directory then visit each subdirectory in turn, leaving with
+    // "cd .." like the puzzle input does
+    DepthFirst,
+    // list every directory at one depth before going any deeper
+    BreadthFirst,
+}
+
+enum Entry {
+    Directory(String),
+    File(String, u64),
+}
+
+impl Entry {
+    fn name(&self) -> &str {
+        match self {
+            Entry::Directory(name) | Entry::File(name, _) => name,
+        }
+    }
+}
+
+// the entries of a directory sorted by name so the transcript doesn't depend
+// on the order the OS returns them in, symlinks and other special files are
+// left out
+fn list(dir: &Path) -> io::Result<Vec<Entry>> {
+    let mut entries = vec![];
+
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let metadata = entry.path().symlink_metadata()?;
+
+        let name = entry.file_name().into_string().map_err(|n| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("{:?} is not valid unicode", n))
+        })?;
+
+        if name.contains('\n') {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{:?} can't be written to a transcript", name),
+            ));
+        }
+
+        if metadata.is_dir() {
+            entries.push(Entry::Directory(name));
+        } else if metadata.is_file() {
+            entries.push(Entry::File(name, metadata.len()));
+        }
+    }
+
+    entries.sort_by(|a, b| a.name().cmp(b.name()));
+
+    Ok(entries)
+}
+
+fn push_listing(lines: &mut Vec<String>, entries: &[Entry]) {
+    lines.push("$ ls".to_string());
+
+    for e in entries {
+        match e {
+            Entry::Directory(name) => lines.push(format!("dir {}", name)),
+            Entry::File(name, size) => lines.push(format!("{} {}", size, name)),
+        }
+    }
+}
+
+fn depth_first(lines: &mut Vec<String>, dir: &Path) -> io::Result<()> {
+    let entries = list(dir)?;
+    push_listing(lines, &entries);
+
+    for e in entries.iter() {
+        if let Entry::Directory(name) = e {
+            lines.push(format!("$ cd {}", name));
+            depth_first(lines, &dir.join(name))?;
+            lines.push("$ cd ..".to_string());
+        }
+    }
+
+    Ok(())
+}
+
+fn breadth_first(lines: &mut Vec<String>, root: &Path) -> io::Result<()> {
+    let mut cwd: Vec<String> = vec![];
+
+    let mut queue: VecDeque<(Vec<String>, PathBuf)> = VecDeque::new();
+    queue.push_back((vec![], root.to_path_buf()));
+
+    while let Some((components, dir)) = queue.pop_front() {
+        // climb up to the closest common directory then down to the next one
+        let common = cwd
+            .iter()
+            .zip(components.iter())
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        for _ in common..cwd.len() {
+            lines.push("$ cd ..".to_string());
+        }
+        for c in components[common..].iter() {
+            lines.push(format!("$ cd {}", c));
+        }
+        cwd = components.clone();
+
+        let entries = list(&dir)?;
+        push_listing(lines, &entries);
+
+        for e in entries.iter() {
+            if let Entry::Directory(name) = e {
+                let mut child = components.clone();
+                child.push(name.clone());
+                queue.push_back((child, dir.join(name)));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Produces the terminal output day7 expects for a real directory, the inverse
+// of parse. Sizes are the apparent file sizes as reported by `du -b`.
+pub fn transcript(root: &Path, order: TraversalOrder) -> io::Result<Vec<String>> {
+    let mut lines = vec!["$ cd /".to_string()];
+
+    match order {
+        TraversalOrder::DepthFirst => depth_first(&mut lines, root)?,
+        TraversalOrder::BreadthFirst => breadth_first(&mut lines, root)?,
+    }
+
+    Ok(lines)
+}
+
+// a directory for a test that's removed again even if the test fails
+#[cfg(test)]
+pub struct TempDir(pub PathBuf);
+
+#[cfg(test)]
+impl TempDir {
+    pub fn new(name: &str) -> TempDir {
+        TempDir(std::env::temp_dir().join(format!("day7-{}-{}", name, std::process::id())))
+    }
+}
+
+#[cfg(test)]
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+#[test]
+fn test_breadth_first_navigation() {
+    let temp = TempDir::new("bfs");
+    let root = &temp.0;
+    fs::create_dir_all(root.join("a/e")).unwrap();
+    fs::create_dir_all(root.join("d")).unwrap();
+    fs::write(root.join("a/e/i"), vec![0; 584]).unwrap();
+    fs::write(root.join("b.txt"), "hello").unwrap();
+
+    let lines = transcript(root, TraversalOrder::BreadthFirst).unwrap();
+
+    assert_eq!(
+        vec![
+            "$ cd /", "$ ls", "dir a", "5 b.txt", "dir d", "$ cd a", "$ ls", "dir e", "$ cd ..",
+            "$ cd d", "$ ls", "$ cd ..", "$ cd a", "$ cd e", "$ ls", "584 i",
+        ],
+        lines
+    );
+}
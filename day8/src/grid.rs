@@ -0,0 +1,376 @@
+use std::fmt::Display;
+
+use rayon::prelude::*;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Format {
+    // one character per tree, the puzzle input
+    Digits,
+    // heights up to 255 separated by commas or whitespace
+    Delimited,
+}
+
+impl Format {
+    // Anything that isn't all digits has to be delimited. A delimited forest
+    // a single tree wide has nothing to tell it apart from digits, those need
+    // the format given.
+    pub fn detect(line: &str) -> Format {
+        if line.chars().all(|c| c.is_ascii_digit()) {
+            Format::Digits
+        } else {
+            Format::Delimited
+        }
+    }
+}
+
+// Reads one row of heights into row, replacing what was there so the buffer
+// can be reused from line to line.
+pub fn parse_row(line: &str, format: Format, row: &mut Vec<u8>) -> Result<(), String> {
+    row.clear();
+
+    match format {
+        Format::Digits => {
+            for c in line.chars() {
+                let h = c.to_digit(10).ok_or(format!("invalid height: {}", c))?;
+                row.push(h as u8);
+            }
+        }
+        Format::Delimited => {
+            for value in line.split(|c: char| c == ',' || c.is_whitespace()) {
+                if value.is_empty() {
+                    continue;
+                }
+
+                let h = value.parse::<u8>().map_err(|_| format!("invalid height: {}", value))?;
+                row.push(h);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Mode {
+    Serial,
+    // rows and columns spread over the rayon thread pool
+    Parallel,
+}
+
+// a single character for a height, heights past 35 don't have one
+pub fn height_char(height: u8) -> char {
+    char::from_digit(height as u32, 36).unwrap_or('#')
+}
+
+// A straight run of trees through the grid, looking in one direction.
+struct Line {
+    start: usize,
+    step: isize,
+    len: usize,
+}
+
+impl Line {
+    fn index(&self, k: usize) -> usize {
+        (self.start as isize + self.step * k as isize) as usize
+    }
+}
+
+pub struct Grid {
+    pub width: usize,
+    pub height: usize,
+    // row-major, one byte per tree
+    pub heights: Vec<u8>,
+    pub visible: Vec<bool>,
+    pub scenic_scores: Vec<u64>,
+}
+
+impl Grid {
+    // the format is taken from the first line when it isn't given
+    pub fn parse(lines: &[String], format: Option<Format>) -> Result<Grid, String> {
+        let format = format.unwrap_or_else(|| lines.first().map_or(Format::Digits, |l| Format::detect(l)));
+
+        let mut heights = vec![];
+        let mut row = vec![];
+        let mut width = None;
+
+        for (y, line) in lines.iter().enumerate() {
+            parse_row(line, format, &mut row).map_err(|e| format!("row {}: {}", y + 1, e))?;
+
+            match width {
+                Some(w) if w != row.len() => {
+                    return Err(format!("row {} has {} trees, expected {}", y + 1, row.len(), w))
+                }
+                _ => width = Some(row.len()),
+            }
+
+            heights.extend_from_slice(&row);
+        }
+
+        Ok(Grid {
+            width: width.unwrap_or(0),
+            height: lines.len(),
+            visible: vec![false; heights.len()],
+            scenic_scores: vec![1; heights.len()],
+            heights,
+        })
+    }
+
+    // every row and column in both directions, the view from each edge, none
+    // for a grid without any trees
+    fn lines(&self) -> Vec<Line> {
+        let (w, h) = (self.width, self.height);
+        let mut lines = vec![];
+
+        if w == 0 || h == 0 {
+            return lines;
+        }
+
+        for y in 0..h {
+            lines.push(Line { start: y * w, step: 1, len: w });
+            lines.push(Line { start: y * w + w - 1, step: -1, len: w });
+        }
+
+        for x in 0..w {
+            lines.push(Line { start: x, step: w as isize, len: h });
+            lines.push(Line { start: x + (h - 1) * w, step: -(w as isize), len: h });
+        }
+
+        lines
+    }
+
+    pub fn update_visibility(&mut self, mode: Mode) {
+        match mode {
+            Mode::Serial => self.visibility_serial(),
+            Mode::Parallel => self.parallel(Pass::Visibility),
+        }
+    }
+
+    pub fn update_scenic_scores(&mut self, mode: Mode) {
+        match mode {
+            Mode::Serial => self.scenic_scores_serial(),
+            Mode::Parallel => self.parallel(Pass::ScenicScores),
+        }
+    }
+
+    fn visibility_serial(&mut self) {
+        for line in self.lines() {
+            let mut highest: Option<u8> = None;
+
+            for k in 0..line.len {
+                let i = line.index(k);
+
+                if highest.is_none_or(|h| self.heights[i] > h) {
+                    self.visible[i] = true;
+                    highest = Some(self.heights[i]);
+                }
+            }
+        }
+    }
+
+    // Multiplies in the viewing distance along each line. The stack holds the
+    // trees that could still block the view of a later one, tallest at the
+    // bottom, so every tree is pushed and popped once per line.
+    fn scenic_scores_serial(&mut self) {
+        let mut stack: Vec<usize> = vec![];
+
+        for line in self.lines() {
+            stack.clear();
+
+            for k in 0..line.len {
+                let i = line.index(k);
+                let h = self.heights[i];
+
+                while let Some(&j) = stack.last() {
+                    if self.heights[line.index(j)] >= h {
+                        break;
+                    }
+                    stack.pop();
+                }
+
+                let distance = match stack.last() {
+                    Some(&j) => k - j,
+                    None => k,
+                };
+                self.scenic_scores[i] *= distance as u64;
+
+                stack.push(k);
+            }
+        }
+    }
+
+    // Every row is independent of the others so they're split between threads,
+    // then the same again for the columns on a transposed copy of the grid
+    // which is merged back in afterwards.
+    fn parallel(&mut self, pass: Pass) {
+        let (w, h) = (self.width, self.height);
+        if w == 0 || h == 0 {
+            return;
+        }
+
+        self.heights
+            .par_chunks(w)
+            .zip(self.visible.par_chunks_mut(w))
+            .zip(self.scenic_scores.par_chunks_mut(w))
+            .for_each(|((heights, visible), scores)| pass.run(heights, visible, scores));
+
+        let transposed = (0..w * h)
+            .into_par_iter()
+            .map(|i| self.heights[(i % h) * w + i / h])
+            .collect::<Vec<u8>>();
+        let mut visible = vec![false; w * h];
+        let mut scores = vec![1; w * h];
+
+        transposed
+            .par_chunks(h)
+            .zip(visible.par_chunks_mut(h))
+            .zip(scores.par_chunks_mut(h))
+            .for_each(|((heights, visible), scores)| pass.run(heights, visible, scores));
+
+        self.visible
+            .par_chunks_mut(w)
+            .zip(self.scenic_scores.par_chunks_mut(w))
+            .enumerate()
+            .for_each(|(y, (row_visible, row_scores))| {
+                for x in 0..w {
+                    row_visible[x] |= visible[x * h + y];
+                    row_scores[x] *= scores[x * h + y];
+                }
+            });
+    }
+
+    pub fn count_visible(&self) -> u32 {
+        self.visible.iter().filter(|v| **v).count() as u32
+    }
+
+    // the index of the tree with the highest scenic score, the first one in
+    // reading order on a tie
+    pub fn best(&self) -> Option<usize> {
+        (0..self.scenic_scores.len()).rev().max_by_key(|i| self.scenic_scores[*i])
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Pass {
+    Visibility,
+    ScenicScores,
+}
+
+impl Pass {
+    // both directions along a single row
+    fn run(self, heights: &[u8], visible: &mut [bool], scores: &mut [u64]) {
+        let n = heights.len();
+
+        for forwards in [true, false] {
+            let index = |k: usize| if forwards { k } else { n - 1 - k };
+
+            match self {
+                Pass::Visibility => {
+                    let mut highest: Option<u8> = None;
+
+                    for k in 0..n {
+                        let i = index(k);
+                        if highest.is_none_or(|h| heights[i] > h) {
+                            visible[i] = true;
+                            highest = Some(heights[i]);
+                        }
+                    }
+                }
+                Pass::ScenicScores => {
+                    let mut stack: Vec<usize> = vec![];
+
+                    for k in 0..n {
+                        let i = index(k);
+                        while stack.last().is_some_and(|j| heights[index(*j)] < heights[i]) {
+                            stack.pop();
+                        }
+
+                        let distance = stack.last().map_or(k, |j| k - j);
+                        scores[i] *= distance as u64;
+
+                        stack.push(k);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Display for Grid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // one character per tree, hidden trees as dots
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let i = y * self.width + x;
+                if self.visible[i] {
+                    write!(f, "{}", height_char(self.heights[i]))?;
+                } else {
+                    write!(f, ".")?;
+                }
+            }
+
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_parallel_matches_serial() {
+    let mut rng = inputgen::Rng::new(35);
+
+    for _ in 0..20 {
+        let width = rng.range(1, 60) as usize;
+        let height = rng.range(1, 60) as usize;
+        let max_height = rng.range(0, 30) as u8;
+
+        let lines = inputgen::day8::forest(&mut rng, width, height, max_height)
+            .lines()
+            .map(|l| l.to_string())
+            .collect::<Vec<String>>();
+
+        let format = if max_height <= 9 { Format::Digits } else { Format::Delimited };
+
+        let mut serial = Grid::parse(&lines, Some(format)).unwrap();
+        serial.update_visibility(Mode::Serial);
+        serial.update_scenic_scores(Mode::Serial);
+
+        let mut parallel = Grid::parse(&lines, Some(format)).unwrap();
+        parallel.update_visibility(Mode::Parallel);
+        parallel.update_scenic_scores(Mode::Parallel);
+
+        assert_eq!(serial.visible, parallel.visible);
+        assert_eq!(serial.scenic_scores, parallel.scenic_scores);
+    }
+
+    for lines in [vec![], vec![String::new(), String::new()]] {
+        let mut grid = Grid::parse(&lines, None).unwrap();
+        grid.update_visibility(Mode::Serial);
+        grid.update_scenic_scores(Mode::Serial);
+
+        assert_eq!((0, None), (grid.count_visible(), grid.best()));
+    }
+}
+
+#[test]
+fn test_parse_formats() {
+    let parse = |lines: &[&str], format: Option<Format>| {
+        Grid::parse(&lines.iter().map(|l| l.to_string()).collect::<Vec<String>>(), format)
+    };
+
+    let digits = parse(&["303", "255"], None).unwrap();
+    let delimited = parse(&["3,0,3", "2, 5 ,5"], None).unwrap();
+    assert_eq!(digits.heights, delimited.heights);
+    assert_eq!((3, 2), (delimited.width, delimited.height));
+
+    let tall = parse(&["10 200 3"], None).unwrap();
+    assert_eq!(vec![10, 200, 3], tall.heights);
+
+    // one tree a row reads as digits unless it's said otherwise
+    let narrow = parse(&["25", "7"], Some(Format::Delimited)).unwrap();
+    assert_eq!((vec![25, 7], 1), (narrow.heights, narrow.width));
+    assert_eq!(Err("row 2 has 1 trees, expected 2".to_string()), parse(&["25", "7"], None).map(|_| ()));
+
+    assert_eq!(Err("row 2 has 2 trees, expected 3".to_string()), parse(&["303", "25"], None).map(|_| ()));
+    assert_eq!(Err("row 1: invalid height: 256".to_string()), parse(&["1,256"], None).map(|_| ()));
+}
@@ -1,172 +1,256 @@
+mod grid;
+mod naive;
+mod render;
+mod sight;
+mod stream;
+
 use std::{
     fs::File,
-    io::{BufRead, BufReader}, fmt::Display,
+    io::{self, BufRead, BufReader},
+    path::Path,
+    time::Instant,
 };
 
-#[derive(PartialEq, Debug)]
-enum TreeState {
-    Unknown,
-    Visible,
-    NotVisible,
-}
+use common::cli::{next_value, parse_number};
+use grid::{height_char, Format, Grid, Mode};
+use inputgen::Rng;
+use render::Layer;
+use sight::{Direction, Sight};
 
-struct Tree {
-    height: u32,
-    state: TreeState,
-    scenic_scores: Vec<u32>
-}
+const USAGE: &str = "usage:
+  day8 [--input FILE] [--format F] [--parallel] solve
+  day8 [--input FILE] [--format F] [--parallel] render [-layer visibility|scenic] [-image FILE] [-scale N]
+  day8 [--input FILE] [--format F] [--parallel] sight (-from X,Y | -outside [DIRECTION,...])
+  day8 [--input FILE|-] [--format F] stream
+  day8 bench [-size N] [-seed N]
 
-struct Grid {
-    rows: Vec<Vec<Tree>>,
-}
+the format is digits or delimited, taken from the first line when it isn't given";
 
-impl Grid {
+fn load(file: &str, format: Option<Format>, mode: Mode) -> Result<Grid, String> {
+    let f = File::open(file).map_err(|e| format!("{}: {}", file, e))?;
+    let lines = BufReader::new(f)
+        .lines()
+        .collect::<Result<Vec<String>, _>>()
+        .map_err(|e| format!("{}: {}", file, e))?;
 
-    fn update_visibility(&mut self) {
-        for row in self.rows.iter_mut() {
-            visibility_impl(row.iter_mut());
-            visibility_impl(row.iter_mut().rev());
-        }
+    let mut grid = Grid::parse(&lines, format).map_err(|e| format!("{}: {}", file, e))?;
+    grid.update_visibility(mode);
 
-        for i in 0..self.rows.get(0).unwrap().len() {
-            visibility_impl(self.rows.iter_mut().map(|row| row.get_mut(i).unwrap()));
-            visibility_impl(self.rows.iter_mut().rev().map(|row| row.get_mut(i).unwrap()));
-        }
-    }
+    Ok(grid)
+}
 
-    fn update_scenic_scores(&mut self) {
-        for row in self.rows.iter_mut() {
-            score_impl(row.iter_mut());
-            score_impl(row.iter_mut().rev());
-        }
+fn grid(file: &str) -> Grid {
+    load(file, None, Mode::Serial).unwrap()
+}
 
-        for i in 0..self.rows.get(0).unwrap().len() {
-            score_impl(self.rows.iter_mut().map(|row| row.get_mut(i).unwrap()));
-            score_impl(self.rows.iter_mut().rev().map(|row| row.get_mut(i).unwrap()));
-        }
-    }
+fn calculate(file: &str) -> u32 {
+    let grid = grid(file);
 
-    fn count_visible(&self) -> u32 {
-        let mut count: u32 = 0;
+    grid.count_visible()
+}
 
-        for row in self.rows.iter() {
-            for tree in row.iter() {
-                if tree.state == TreeState::Visible {
-                    count += 1;
-                }
-            }
-        }
+fn calculate_part2(file: &str) -> u64 {
+    let mut grid = grid(file);
 
-        count
-    }
+    grid.update_scenic_scores(Mode::Serial);
 
+    grid.scenic_scores[grid.best().unwrap()]
 }
 
-impl Display for Grid {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for row in self.rows.iter() {
-            for tree in row.iter() {
-                write!(f, "{} ({:?}) ", tree.height, tree.state)?;
-            }
-
-            write!(f, "\n")?;
-        }
+fn generated_forest(seed: u64, size: usize) -> Vec<String> {
+    inputgen::day8::forest(&mut Rng::new(seed), size, size, 9)
+        .lines()
+        .map(|l| l.to_string())
+        .collect()
+}
 
-        Ok(())
+// times both parts on a generated forest with the original implementation
+// and the stack based one, serial and parallel
+fn bench(size: usize, seed: u64) {
+    let lines = generated_forest(seed, size);
+    println!("forest: {}x{}, seed {}", size, size, seed);
+
+    let start = Instant::now();
+    let mut naive = naive::Grid::parse(&lines);
+    naive.update_visibility();
+    naive.update_scenic_scores();
+    let naive_best = naive.scenic_scores().into_iter().max().unwrap();
+    let naive_visible = naive.visible().iter().filter(|v| **v).count();
+    println!("naive: {:?} (visible {}, best {})", start.elapsed(), naive_visible, naive_best);
+
+    for (name, mode) in [("stack", Mode::Serial), ("parallel", Mode::Parallel)] {
+        let start = Instant::now();
+        let mut grid = Grid::parse(&lines, Some(Format::Digits)).unwrap();
+        grid.update_visibility(mode);
+        grid.update_scenic_scores(mode);
+        let best = *grid.scenic_scores.iter().max().unwrap();
+        println!("{}: {:?} (visible {}, best {})", name, start.elapsed(), grid.count_visible(), best);
     }
 }
 
-fn score_impl<'a>(iter: impl Iterator<Item = &'a mut Tree>) {
-    let mut history: Vec<&mut Tree> = vec![];
+fn parse_position(value: &str) -> Result<(usize, usize), String> {
+    let (x, y) = value.split_once(',').ok_or(format!("invalid position: {}", value))?;
 
-    for tree in iter {
-        let mut calculated = false;
+    Ok((parse_number(x)?, parse_number(y)?))
+}
 
-        for (score, t) in history.iter().rev().enumerate() {
-            if t.height >= tree.height {
-                tree.scenic_scores.push(score as u32 + 1);
-                calculated = true;
-                break;
-            }
-        }
+fn parse_directions(value: &str) -> Result<Vec<Direction>, String> {
+    value
+        .split(',')
+        .map(|d| Direction::parse(d).ok_or(format!("unknown direction: {}", d)))
+        .collect()
+}
 
-        if !calculated {
-            tree.scenic_scores.push(history.len() as u32);
-        }
+// the distances followed by the grid with everything out of sight as dots
+fn print_sight(grid: &Grid, sight: &Sight, from: Option<(usize, usize)>) {
+    for (direction, distance) in sight.distances.iter() {
+        println!("{}\t{}", direction.name(), distance);
+    }
+    println!("visible: {}", sight.visible.len());
+
+    for y in 0..grid.height {
+        let row = (0..grid.width)
+            .map(|x| {
+                if from == Some((x, y)) {
+                    '@'
+                } else if sight.visible.contains(&(x, y)) {
+                    height_char(grid.heights[y * grid.width + x])
+                } else {
+                    '.'
+                }
+            })
+            .collect::<String>();
 
-        history.push(tree);
+        println!("{}", row);
     }
 }
 
-fn visibility_impl<'a>(iter: impl Iterator<Item = &'a mut Tree>) {
-    let mut highest: Option<u32> = None;
-    for tree in iter {
-        match highest {
-            Some(height) => {
-                if tree.height > height {
-                    tree.state = TreeState::Visible;
-                    highest = Some(tree.height);
-                } else if tree.state == TreeState::Unknown {
-                    tree.state = TreeState::NotVisible;
+fn run_command(args: &[String]) -> Result<(), String> {
+    let mut input = "input/problem.txt";
+    let mut args = args.iter();
+
+    let mut mode = Mode::Serial;
+    let mut format = None;
+
+    let mut command = args.next().ok_or("missing command")?;
+    loop {
+        match command.as_str() {
+            "--input" => input = next_value(&mut args, command)?,
+            "--parallel" => mode = Mode::Parallel,
+            "--format" => {
+                format = match next_value(&mut args, command)?.as_str() {
+                    "digits" => Some(Format::Digits),
+                    "delimited" => Some(Format::Delimited),
+                    f => return Err(format!("unknown format: {}", f)),
                 }
-            },
-            None => {
-                tree.state = TreeState::Visible;
-                highest = Some(tree.height);
             }
+            _ => break,
         }
+        command = args.next().ok_or("missing command")?;
     }
-}
-
-fn grid(file: &str) -> Grid {
 
-    let mut rows: Vec<Vec<Tree>> = vec![];
-
-    let file = File::open(file).unwrap();
-    for line in BufReader::new(file).lines().map(|l| l.unwrap()) {
-        let mut row: Vec<Tree> = vec![]; 
-
-        for c in line.chars() {
-            let h = c.to_string().parse::<u32>().unwrap();
-            row.push(Tree { height: h, state: TreeState::Unknown, scenic_scores: vec![] })
+    let mut layer = Layer::Visibility;
+    let mut image = None;
+    let mut scale = 4;
+    let mut from = None;
+    let mut outside = None;
+    let mut size = 1000;
+    let mut seed = 1;
+
+    while let Some(arg) = args.next() {
+        match (command.as_str(), arg.as_str()) {
+            ("render", "-layer") => {
+                layer = match next_value(&mut args, arg)?.as_str() {
+                    "visibility" => Layer::Visibility,
+                    "scenic" => Layer::Scenic,
+                    l => return Err(format!("unknown layer: {}", l)),
+                }
+            }
+            ("render", "-image") => image = Some(next_value(&mut args, arg)?),
+            ("render", "-scale") => scale = parse_number(next_value(&mut args, arg)?)?,
+            ("sight", "-from") => from = Some(parse_position(next_value(&mut args, arg)?)?),
+            ("sight", "-outside") => {
+                // the directions are optional, without them look in all 8
+                outside = match args.clone().next().filter(|a| !a.starts_with('-')) {
+                    Some(value) => {
+                        args.next();
+                        Some(parse_directions(value)?)
+                    }
+                    None => Some(Direction::ALL.to_vec()),
+                }
+            }
+            ("bench", "-size") => size = parse_number(next_value(&mut args, arg)?)?,
+            ("bench", "-seed") => seed = parse_number(next_value(&mut args, arg)?)?,
+            (_, a) => return Err(format!("unknown option for {}: {}", command, a)),
         }
-
-        rows.push(row);
     }
 
-    let mut g = Grid { rows: rows };
-    g.update_visibility();
-
-    g
-}
+    match command.as_str() {
+        "solve" => {
+            let mut grid = load(input, format, mode)?;
+            grid.update_scenic_scores(mode);
 
-fn calculate(file: &str) -> u32 {
-    let grid = grid(file);
+            println!("result: {}", grid.count_visible());
+            println!("result part 2: {}", grid.best().map_or(0, |i| grid.scenic_scores[i]));
+        }
+        "render" => {
+            let mut grid = load(input, format, mode)?;
+            grid.update_scenic_scores(mode);
+
+            match image {
+                Some(path) => render::write_image(&grid, layer, scale, Path::new(path))
+                    .map_err(|e| format!("{}: {}", path, e))?,
+                None => print!("{}", render::ansi(&grid, layer)),
+            }
 
-    grid.count_visible()
-}
+            if let Some(best) = grid.best() {
+                println!(
+                    "best: ({}, {}) scenic score {}",
+                    best % grid.width,
+                    best / grid.width,
+                    grid.scenic_scores[best]
+                );
+            }
+        }
+        "sight" => {
+            let grid = load(input, format, mode)?;
 
-fn calculate_part2(file: &str) -> u32 {
-    let mut grid = grid(file);
+            let sight = match (from, outside) {
+                (Some((x, y)), None) if x < grid.width && y < grid.height => grid.sight_from(x, y),
+                (Some((x, y)), None) => return Err(format!("{},{} is outside the grid", x, y)),
+                (None, Some(directions)) => grid.sight_from_outside(&directions),
+                _ => return Err("sight needs one of -from or -outside".to_string()),
+            };
 
-    grid.update_scenic_scores();
+            print_sight(&grid, &sight, from);
+        }
+        "stream" => {
+            let summary = if input == "-" {
+                stream::solve(io::stdin().lock(), format)
+            } else {
+                let f = File::open(input).map_err(|e| format!("{}: {}", input, e))?;
+                stream::solve(BufReader::new(f), format)
+            }
+            .map_err(|e| format!("{}: {}", input, e))?;
 
-    let mut max: u32 = 0;
-    for row in grid.rows.iter() {
-        for tree in row.iter() {
-            let score = tree.scenic_scores.iter().product();
-            if score > max {
-                max = score;
+            println!("forest: {}x{}", summary.width, summary.height);
+            println!("visible: {}", summary.visible);
+            if let Some((x, y, score)) = summary.best {
+                println!("best: ({}, {}) scenic score {}", x, y, score);
             }
         }
+        "bench" => bench(size, seed),
+        c => return Err(format!("unknown command: {}", c)),
     }
 
-    max 
+    Ok(())
 }
 
 fn main() {
-    println!("result: {}", calculate("input/problem.txt"));
-    println!("result part 2: {}", calculate_part2("input/problem.txt"));
+    common::cli::main(USAGE, run_command, || {
+        println!("result: {}", calculate("input/problem.txt"));
+        println!("result part 2: {}", calculate_part2("input/problem.txt"));
+    });
 }
 
 #[test]
@@ -177,4 +261,22 @@ fn test_example() {
 #[test]
 fn test_example_part2() {
     assert_eq!(8, calculate_part2("input/example.txt"));
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_matches_naive() {
+    for (seed, size) in [(1, 1), (2, 2), (3, 17), (4, 60)] {
+        let lines = generated_forest(seed, size);
+
+        let mut naive = naive::Grid::parse(&lines);
+        naive.update_visibility();
+        naive.update_scenic_scores();
+
+        let mut grid = Grid::parse(&lines, Some(Format::Digits)).unwrap();
+        grid.update_visibility(Mode::Serial);
+        grid.update_scenic_scores(Mode::Serial);
+
+        assert_eq!(naive.visible(), grid.visible);
+        assert_eq!(naive.scenic_scores(), grid.scenic_scores);
+    }
+}
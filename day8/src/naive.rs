@@ -0,0 +1,126 @@
+// The original implementation, which keeps a history of every tree seen so far
+// in a line and searches back through it for each tree. It's kept to check
+// the stack based version in main against and to benchmark it.
+
+#[derive(PartialEq, Debug)]
+enum TreeState {
+    Unknown,
+    Visible,
+    NotVisible,
+}
+
+struct Tree {
+    height: u32,
+    state: TreeState,
+    scenic_scores: Vec<u32>,
+}
+
+pub struct Grid {
+    rows: Vec<Vec<Tree>>,
+}
+
+impl Grid {
+    pub fn parse(lines: &[String]) -> Grid {
+        let mut rows: Vec<Vec<Tree>> = vec![];
+
+        for line in lines {
+            let mut row: Vec<Tree> = vec![];
+
+            for c in line.chars() {
+                let h = c.to_string().parse::<u32>().unwrap();
+                row.push(Tree {
+                    height: h,
+                    state: TreeState::Unknown,
+                    scenic_scores: vec![],
+                })
+            }
+
+            rows.push(row);
+        }
+
+        Grid { rows }
+    }
+
+    pub fn update_visibility(&mut self) {
+        for row in self.rows.iter_mut() {
+            visibility_impl(row.iter_mut());
+            visibility_impl(row.iter_mut().rev());
+        }
+
+        for i in 0..self.rows.first().unwrap().len() {
+            visibility_impl(self.rows.iter_mut().map(|row| row.get_mut(i).unwrap()));
+            visibility_impl(self.rows.iter_mut().rev().map(|row| row.get_mut(i).unwrap()));
+        }
+    }
+
+    pub fn update_scenic_scores(&mut self) {
+        for row in self.rows.iter_mut() {
+            score_impl(row.iter_mut());
+            score_impl(row.iter_mut().rev());
+        }
+
+        for i in 0..self.rows.first().unwrap().len() {
+            score_impl(self.rows.iter_mut().map(|row| row.get_mut(i).unwrap()));
+            score_impl(self.rows.iter_mut().rev().map(|row| row.get_mut(i).unwrap()));
+        }
+    }
+
+    // row-major, the same layout as the grid in main
+    pub fn visible(&self) -> Vec<bool> {
+        self.rows
+            .iter()
+            .flatten()
+            .map(|t| t.state == TreeState::Visible)
+            .collect()
+    }
+
+    pub fn scenic_scores(&self) -> Vec<u64> {
+        self.rows
+            .iter()
+            .flatten()
+            .map(|t| t.scenic_scores.iter().map(|s| *s as u64).product())
+            .collect()
+    }
+}
+
+fn score_impl<'a>(iter: impl Iterator<Item = &'a mut Tree>) {
+    let mut history: Vec<&mut Tree> = vec![];
+
+    for tree in iter {
+        let mut calculated = false;
+
+        for (score, t) in history.iter().rev().enumerate() {
+            if t.height >= tree.height {
+                tree.scenic_scores.push(score as u32 + 1);
+                calculated = true;
+                break;
+            }
+        }
+
+        if !calculated {
+            tree.scenic_scores.push(history.len() as u32);
+        }
+
+        history.push(tree);
+    }
+}
+
+fn visibility_impl<'a>(iter: impl Iterator<Item = &'a mut Tree>) {
+    let mut highest: Option<u32> = None;
+    for tree in iter {
+        match highest {
+            Some(height) => {
+                if tree.height > height {
+                    tree.state = TreeState::Visible;
+                    highest = Some(tree.height);
+                } else if tree.state == TreeState::Unknown {
+                    tree.state = TreeState::NotVisible;
+                }
+            },
+            None => {
+                tree.state = TreeState::Visible;
+                highest = Some(tree.height);
+            }
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use crate::grid::{height_char, Grid};
+
+type Rgb = (u8, u8, u8);
+
+const VISIBLE: Rgb = (46, 139, 87);
+const HIDDEN: Rgb = (40, 40, 48);
+const BEST: Rgb = (255, 0, 255);
+
+// dark to bright, interpolated between these for the scenic heatmap
+const HEAT: [Rgb; 5] = [
+    (0, 0, 4),
+    (87, 16, 110),
+    (188, 55, 84),
+    (249, 142, 9),
+    (252, 255, 164),
+];
+
+#[derive(Clone, Copy)]
+pub enum Layer {
+    Visibility,
+    Scenic,
+}
+
+fn heat(t: f64) -> Rgb {
+    let t = t.clamp(0.0, 1.0) * (HEAT.len() - 1) as f64;
+    let i = (t as usize).min(HEAT.len() - 2);
+    let f = t - i as f64;
+
+    let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * f).round() as u8;
+    let (a, b) = (HEAT[i], HEAT[i + 1]);
+
+    (mix(a.0, b.0), mix(a.1, b.1), mix(a.2, b.2))
+}
+
+// the colour of every cell, row-major. Scores cover several orders of
+// magnitude so the heatmap uses a log scale, otherwise everything but the
+// few best trees would be black.
+fn colours(grid: &Grid, layer: Layer) -> Vec<Rgb> {
+    let best = grid.best();
+    let max = (*grid.scenic_scores.iter().max().unwrap_or(&0) as f64).ln_1p();
+
+    (0..grid.heights.len())
+        .map(|i| {
+            if Some(i) == best {
+                return BEST;
+            }
+
+            match layer {
+                Layer::Visibility if grid.visible[i] => VISIBLE,
+                Layer::Visibility => HIDDEN,
+                Layer::Scenic if max == 0.0 => heat(0.0),
+                Layer::Scenic => heat((grid.scenic_scores[i] as f64).ln_1p() / max),
+            }
+        })
+        .collect()
+}
+
+// One character per tree, the height drawn over a 24-bit background colour.
+// The best tree is shown in bold.
+pub fn ansi(grid: &Grid, layer: Layer) -> String {
+    let colours = colours(grid, layer);
+    let best = grid.best();
+    let mut out = String::new();
+
+    for y in 0..grid.height {
+        for x in 0..grid.width {
+            let i = y * grid.width + x;
+            let (r, g, b) = colours[i];
+
+            // keep the digit readable on bright backgrounds
+            let luma = 0.299 * r as f64 + 0.587 * g as f64 + 0.114 * b as f64;
+            let fg = if luma > 140.0 { 30 } else { 97 };
+            let bold = if Some(i) == best { "1;" } else { "" };
+
+            out.push_str(&format!("\x1b[{}{};48;2;{};{};{}m{}", bold, fg, r, g, b, height_char(grid.heights[i])));
+        }
+
+        out.push_str("\x1b[0m\n");
+    }
+
+    out
+}
+
+// Writes a binary PPM with each tree drawn as a scale x scale square.
+pub fn write_image(grid: &Grid, layer: Layer, scale: usize, path: &Path) -> io::Result<()> {
+    let colours = colours(grid, layer);
+    let mut out = BufWriter::new(File::create(path)?);
+
+    write!(out, "P6\n{} {}\n255\n", grid.width * scale, grid.height * scale)?;
+
+    for y in 0..grid.height {
+        let row = &colours[y * grid.width..(y + 1) * grid.width];
+
+        let mut pixels = Vec::with_capacity(row.len() * scale * 3);
+        for (r, g, b) in row {
+            for _ in 0..scale {
+                pixels.extend([*r, *g, *b]);
+            }
+        }
+
+        for _ in 0..scale {
+            out.write_all(&pixels)?;
+        }
+    }
+
+    out.flush()
+}
+
+#[test]
+fn test_render_example() {
+    let mut grid = crate::grid("input/example.txt");
+    grid.update_scenic_scores(crate::grid::Mode::Serial);
+
+    let text = ansi(&grid, Layer::Visibility);
+    assert_eq!(5, text.lines().count());
+    // the best tree is the 5 in the middle of the fourth row
+    assert!(text.contains("\x1b[1;97;48;2;255;0;255m5"));
+
+    let path = std::env::temp_dir().join(format!("day8-render-{}.ppm", std::process::id()));
+    write_image(&grid, Layer::Scenic, 3, &path).unwrap();
+    let bytes = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let header = b"P6\n15 15\n255\n";
+    assert_eq!(header.len() + 15 * 15 * 3, bytes.len());
+    assert_eq!(&header[..], &bytes[..header.len()]);
+
+    // the corners score 0 so they're the darkest colour
+    assert_eq!(&[0, 0, 4], &bytes[header.len()..header.len() + 3]);
+}
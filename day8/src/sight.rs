@@ -0,0 +1,205 @@
+use std::collections::BTreeSet;
+
+use crate::grid::Grid;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Direction {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 8] = [
+        Direction::N,
+        Direction::NE,
+        Direction::E,
+        Direction::SE,
+        Direction::S,
+        Direction::SW,
+        Direction::W,
+        Direction::NW,
+    ];
+
+    // y grows downwards like the rows of the input
+    fn offset(self) -> (isize, isize) {
+        match self {
+            Direction::N => (0, -1),
+            Direction::NE => (1, -1),
+            Direction::E => (1, 0),
+            Direction::SE => (1, 1),
+            Direction::S => (0, 1),
+            Direction::SW => (-1, 1),
+            Direction::W => (-1, 0),
+            Direction::NW => (-1, -1),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Direction::N => "N",
+            Direction::NE => "NE",
+            Direction::E => "E",
+            Direction::SE => "SE",
+            Direction::S => "S",
+            Direction::SW => "SW",
+            Direction::W => "W",
+            Direction::NW => "NW",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Direction> {
+        Direction::ALL
+            .into_iter()
+            .find(|d| d.name().eq_ignore_ascii_case(s))
+    }
+}
+
+pub struct Sight {
+    // (x, y) of every tree that can be seen
+    pub visible: BTreeSet<(usize, usize)>,
+    // how many trees deep the view reaches in each direction asked for
+    pub distances: Vec<(Direction, usize)>,
+}
+
+impl Grid {
+    fn step(&self, (x, y): (usize, usize), direction: Direction) -> Option<(usize, usize)> {
+        let (dx, dy) = direction.offset();
+        let x = x.checked_add_signed(dx).filter(|x| *x < self.width)?;
+        let y = y.checked_add_signed(dy).filter(|y| *y < self.height)?;
+
+        Some((x, y))
+    }
+
+    fn height_at(&self, (x, y): (usize, usize)) -> u8 {
+        self.heights[y * self.width + x]
+    }
+
+    // the cells from a position to the edge of the grid, not including the
+    // position itself
+    fn ray(&self, from: (usize, usize), direction: Direction) -> impl Iterator<Item = (usize, usize)> + '_ {
+        std::iter::successors(self.step(from, direction), move |p| self.step(*p, direction))
+    }
+
+    // The trees seen from the top of the tree at (x, y). Like the scenic score
+    // the view in each direction stops at the edge or at the first tree at
+    // least as tall as the one being stood on, which is seen too.
+    pub fn sight_from(&self, x: usize, y: usize) -> Sight {
+        let height = self.height_at((x, y));
+        let mut sight = Sight {
+            visible: BTreeSet::new(),
+            distances: vec![],
+        };
+
+        for direction in Direction::ALL {
+            let mut distance = 0;
+
+            for p in self.ray((x, y), direction) {
+                sight.visible.insert(p);
+                distance += 1;
+
+                if self.height_at(p) >= height {
+                    break;
+                }
+            }
+
+            sight.distances.push((direction, distance));
+        }
+
+        sight
+    }
+
+    // The trees seen from outside the grid looking in each of the directions,
+    // a tree is visible when it's taller than every tree in front of it. The
+    // distance is how far in from the edge the furthest of them is.
+    pub fn sight_from_outside(&self, directions: &[Direction]) -> Sight {
+        let mut sight = Sight {
+            visible: BTreeSet::new(),
+            distances: vec![],
+        };
+
+        for direction in directions.iter().copied() {
+            let (dx, dy) = direction.offset();
+            let mut furthest = 0;
+
+            // every line starts on a cell with nothing before it in the grid
+            let starts = (0..self.height)
+                .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+                .filter(|(x, y)| {
+                    x.checked_add_signed(-dx).is_none_or(|x| x >= self.width)
+                        || y.checked_add_signed(-dy).is_none_or(|y| y >= self.height)
+                });
+
+            for start in starts {
+                let line = std::iter::once(start).chain(self.ray(start, direction));
+                let mut highest: Option<u8> = None;
+
+                for (k, p) in line.enumerate() {
+                    if highest.is_none_or(|h| self.height_at(p) > h) {
+                        sight.visible.insert(p);
+                        highest = Some(self.height_at(p));
+                        furthest = furthest.max(k + 1);
+                    }
+                }
+            }
+
+            sight.distances.push((direction, furthest));
+        }
+
+        sight
+    }
+}
+
+#[test]
+fn test_sight_from_tree() {
+    let mut grid = crate::grid("input/example.txt");
+    grid.update_scenic_scores(crate::grid::Mode::Serial);
+
+    let sight = grid.sight_from(2, 3);
+    let distance = |d: Direction| sight.distances.iter().find(|(e, _)| *e == d).unwrap().1;
+
+    assert_eq!((2, 2, 1, 2), (distance(Direction::N), distance(Direction::W), distance(Direction::S), distance(Direction::E)));
+    assert_eq!((2, 1, 1, 1), (distance(Direction::NE), distance(Direction::NW), distance(Direction::SE), distance(Direction::SW)));
+    assert_eq!(12, sight.visible.len());
+    assert!(sight.visible.contains(&(4, 1)));
+
+    // the straight directions give back the scenic score of every tree
+    for y in 0..grid.height {
+        for x in 0..grid.width {
+            let sight = grid.sight_from(x, y);
+            let score = sight
+                .distances
+                .iter()
+                .filter(|(d, _)| matches!(d, Direction::N | Direction::E | Direction::S | Direction::W))
+                .map(|(_, distance)| *distance as u64)
+                .product::<u64>();
+
+            assert_eq!(grid.scenic_scores[y * grid.width + x], score);
+        }
+    }
+}
+
+#[test]
+fn test_sight_from_outside() {
+    let grid = crate::grid("input/example.txt");
+
+    let straight = [Direction::N, Direction::E, Direction::S, Direction::W];
+    let sight = grid.sight_from_outside(&straight);
+
+    let expected = (0..grid.height)
+        .flat_map(|y| (0..grid.width).map(move |x| (x, y)))
+        .filter(|(x, y)| grid.visible[y * grid.width + x])
+        .collect::<BTreeSet<(usize, usize)>>();
+    assert_eq!(expected, sight.visible);
+
+    // on the diagonal starting at (0, 3) the 5 at (2, 1) is hidden behind the
+    // 5 at (1, 2) but the 7 at (3, 0) is seen over both
+    let diagonal = grid.sight_from_outside(&[Direction::NE]);
+    assert!(diagonal.visible.contains(&(3, 0)));
+    assert!(!diagonal.visible.contains(&(2, 1)));
+}
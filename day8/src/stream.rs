@@ -0,0 +1,196 @@
+use std::io::BufRead;
+
+use crate::grid::{parse_row, Format};
+
+// Solves both parts a row at a time without keeping the forest in memory.
+//
+// Rows give the left and right views directly. Every column keeps a few
+// stacks of heights, strictly decreasing so they hold at most one entry per
+// distinct height:
+// - up: trees that can still block the view upwards of a later tree
+// - pending: trees with nothing seen from below them yet, which stay visible
+//   from the bottom unless a tree at least as tall turns up later
+// - waiting: trees whose view downwards hasn't reached a blocker yet, with
+//   the product of their other three viewing distances
+//
+// so memory is bounded by the width times the number of distinct heights
+// however many rows there are.
+
+pub struct Summary {
+    pub width: usize,
+    pub height: usize,
+    pub visible: u64,
+    // (x, y, scenic score) of the best tree, the first in reading order on
+    // a tie
+    pub best: Option<(usize, usize, u64)>,
+}
+
+#[derive(Default)]
+struct Column {
+    highest: Option<u8>,
+    up: Vec<(u8, usize)>,
+    pending: Vec<u8>,
+    waiting: Vec<(u8, usize, u64)>,
+}
+
+struct Best(Option<(usize, usize, u64)>);
+
+impl Best {
+    fn offer(&mut self, x: usize, y: usize, score: u64) {
+        let better = self
+            .0
+            .is_none_or(|(bx, by, b)| score > b || (score == b && (y, x) < (by, bx)));
+
+        if better {
+            self.0 = Some((x, y, score));
+        }
+    }
+}
+
+// for each tree the distance to the closest tree at least as tall on its
+// left, or to the edge, and whether it's taller than everything on its left
+fn row_views(row: &[u8], stack: &mut Vec<usize>, distances: &mut [u64], visible: &mut [bool], indices: impl Iterator<Item = usize>) {
+    stack.clear();
+
+    for (k, i) in indices.enumerate() {
+        while stack.last().is_some_and(|j| row[*j] < row[i]) {
+            stack.pop();
+        }
+
+        distances[i] = match stack.last() {
+            Some(j) => j.abs_diff(i) as u64,
+            None => k as u64,
+        };
+        visible[i] = stack.is_empty();
+
+        stack.push(i);
+    }
+}
+
+pub fn solve(reader: impl BufRead, format: Option<Format>) -> Result<Summary, String> {
+    let mut format = format;
+    let mut columns: Vec<Column> = vec![];
+    let mut width = None;
+    let mut height = 0;
+    let mut visible = 0;
+    let mut best = Best(None);
+
+    let mut row = vec![];
+    let mut stack = vec![];
+    let (mut left, mut right) = (vec![], vec![]);
+    let (mut seen_left, mut seen_right) = (vec![], vec![]);
+
+    for line in reader.lines() {
+        let line = line.map_err(|e| e.to_string())?;
+        let y = height;
+
+        let format = *format.get_or_insert_with(|| Format::detect(&line));
+        parse_row(&line, format, &mut row).map_err(|e| format!("row {}: {}", y + 1, e))?;
+
+        let w = *width.get_or_insert(row.len());
+        if row.len() != w {
+            return Err(format!("row {} has {} trees, expected {}", y + 1, row.len(), w));
+        }
+
+        if columns.is_empty() {
+            columns.resize_with(w, Column::default);
+            left.resize(w, 0);
+            right.resize(w, 0);
+            seen_left.resize(w, false);
+            seen_right.resize(w, false);
+        }
+
+        row_views(&row, &mut stack, &mut left, &mut seen_left, 0..w);
+        row_views(&row, &mut stack, &mut right, &mut seen_right, (0..w).rev());
+
+        for (x, column) in columns.iter_mut().enumerate() {
+            let h = row[x];
+
+            // anything waiting for a view downwards is blocked here
+            while let Some(&(wh, wy, partial)) = column.waiting.last() {
+                if wh > h {
+                    break;
+                }
+                best.offer(x, wy, partial * (y - wy) as u64);
+                column.waiting.pop();
+            }
+
+            while column.pending.last().is_some_and(|p| *p <= h) {
+                column.pending.pop();
+            }
+
+            while column.up.last().is_some_and(|(uh, _)| *uh < h) {
+                column.up.pop();
+            }
+            let up = match column.up.last() {
+                Some((_, uy)) => (y - uy) as u64,
+                None => y as u64,
+            };
+            if column.up.last().is_some_and(|(uh, _)| *uh == h) {
+                column.up.pop();
+            }
+            column.up.push((h, y));
+
+            let seen_top = column.highest.is_none_or(|t| h > t);
+            if seen_top {
+                column.highest = Some(h);
+            }
+
+            if seen_top || seen_left[x] || seen_right[x] {
+                visible += 1;
+            } else {
+                column.pending.push(h);
+            }
+
+            column.waiting.push((h, y, left[x] * right[x] * up));
+        }
+
+        height += 1;
+    }
+
+    // what's left can see all the way down to the bottom edge
+    for (x, column) in columns.iter().enumerate() {
+        visible += column.pending.len() as u64;
+
+        for (_, wy, partial) in column.waiting.iter() {
+            best.offer(x, *wy, partial * (height - 1 - wy) as u64);
+        }
+    }
+
+    Ok(Summary {
+        width: width.unwrap_or(0),
+        height,
+        visible,
+        best: best.0,
+    })
+}
+
+#[test]
+fn test_matches_grid() {
+    use crate::grid::{Format, Grid, Mode};
+    use inputgen::Rng;
+
+    let mut rng = Rng::new(8);
+
+    for (width, height, max_height) in [(1, 1, 9), (5, 1, 9), (1, 7, 9), (40, 25, 9), (30, 30, 3), (25, 40, 200), (1, 9, 200)] {
+        let lines = inputgen::day8::forest(&mut rng, width, height, max_height)
+            .lines()
+            .map(|l| l.to_string())
+            .collect::<Vec<String>>();
+
+        let format = if max_height <= 9 { Format::Digits } else { Format::Delimited };
+
+        let mut grid = Grid::parse(&lines, Some(format)).unwrap();
+        grid.update_visibility(Mode::Serial);
+        grid.update_scenic_scores(Mode::Serial);
+        let best = grid.best().unwrap();
+
+        let summary = solve(lines.join("\n").as_bytes(), Some(format)).unwrap();
+        assert_eq!((width, height), (summary.width, summary.height));
+        assert_eq!(grid.count_visible() as u64, summary.visible);
+        assert_eq!(
+            Some((best % width, best / width, grid.scenic_scores[best])),
+            summary.best
+        );
+    }
+}
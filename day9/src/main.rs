@@ -1,191 +1,230 @@
+mod physics;
+mod point;
+mod render;
+mod rope;
+
 use std::{
     fs::File,
     io::{BufRead, BufReader},
+    path::Path,
 };
 
-use itertools::Itertools;
+use common::cli::{input_and_command, next_value, parse_number};
+use physics::Standard;
+use point::Point;
+use render::{Animation, Canvas};
+use rope::Rope;
+use rustc_hash::FxHashSet;
+
+const USAGE: &str = "usage:
+  day9 [--input FILE] simulate [-knots N] [-physics P] [-3d]
+  day9 [--input FILE] draw [-knots N] [-physics P]
+  day9 [--input FILE] animate [-knots N] [-physics P] [-every STEPS] [-delay SECONDS] [-scale N] OUTPUT.cast|OUTPUT.gif
+
+physics: standard, 4-connected or elastic:LENGTH";
+
+// A move along any of the axes at once, so UL goes up and left. Forward and
+// back are along z for 3D ropes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Direction([i32; 3]);
+
+impl Direction {
+    fn parse(token: &str, dimensions: usize) -> Result<Direction, String> {
+        let mut offset = [0; 3];
+
+        for c in token.chars() {
+            let (axis, sign) = match c {
+                'R' => (0, 1),
+                'L' => (0, -1),
+                'U' => (1, 1),
+                'D' => (1, -1),
+                'F' if dimensions == 3 => (2, 1),
+                'B' if dimensions == 3 => (2, -1),
+                _ => return Err(format!("unsupported direction: {}", token)),
+            };
+
+            if offset[axis] != 0 {
+                return Err(format!("{} moves along the same axis twice", token));
+            }
+            offset[axis] = sign;
+        }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Point {
-    x: i32,
-    y: i32,
-}
+        if token.is_empty() {
+            return Err("missing direction".to_string());
+        }
 
-impl Point {
-    fn new(x: i32, y: i32) -> Point {
-        Point { x: x, y: y }
+        Ok(Direction(offset))
     }
 }
 
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
 struct Instruction {
     direction: Direction,
     count: u32,
 }
 
-fn move_leader(p: &mut Point, d: &Direction) {
-    *p = match d {
-        Direction::Up => Point { x: p.x, y: p.y + 1 },
-        Direction::Down => Point { x: p.x, y: p.y - 1 },
-        Direction::Left => Point { x: p.x - 1, y: p.y },
-        Direction::Right => Point { x: p.x + 1, y: p.y },
-    };
-}
-
-fn move_follower(follower: &mut Point, leader: &Point) {
-    let x_diff: i32 = leader.x as i32 - follower.x as i32;
-    let y_diff: i32 = leader.y as i32 - follower.y as i32;
-
-    let replacement = match (x_diff, y_diff) {
-        (0, 2) => Point::new(follower.x, follower.y + 1),
-        (0, -2) => Point::new(follower.x, follower.y - 1),
-        (2, 0) => Point::new(follower.x + 1, follower.y),
-        (-2, 0) => Point::new(follower.x - 1, follower.y),
-        (1, 2) => Point::new(follower.x + 1, follower.y + 1),
-        (2, 1) => Point::new(follower.x + 1, follower.y + 1),
-        (2, -1) => Point::new(follower.x + 1, follower.y - 1),
-        (1, -2) => Point::new(follower.x + 1, follower.y - 1),
-        (-1, -2) => Point::new(follower.x - 1, follower.y - 1),
-        (-2, -1) => Point::new(follower.x - 1, follower.y - 1),
-        (-2, 1) => Point::new(follower.x - 1, follower.y + 1),
-        (-1, 2) => Point::new(follower.x - 1, follower.y + 1),
-        (2, 2) => Point::new(follower.x + 1, follower.y + 1),
-        (-2, 2) => Point::new(follower.x - 1, follower.y + 1),
-        (2, -2) => Point::new(follower.x + 1, follower.y - 1),
-        (-2, -2) => Point::new(follower.x - 1, follower.y - 1),
-        _ => follower.clone(),
-    };
-
-    *follower = replacement;
-}
-
-fn load_instructions(file: &str) -> Vec<Instruction> {
-    let mut result: Vec<Instruction> = vec![];
+fn move_leader<const D: usize>(p: &mut Point<D>, d: &Direction) {
+    for (c, offset) in p.0.iter_mut().zip(d.0) {
+        *c += offset;
+    }
+}
 
-    let file = File::open(file).unwrap();
-    for line in BufReader::new(file).lines().map(|l| l.unwrap()) {
-        let tokens: Vec<&str> = line.split(" ").collect();
+// where count steps in the direction end up
+fn moved<const D: usize>(p: Point<D>, d: &Direction, count: i32) -> Point<D> {
+    Point(std::array::from_fn(|i| p.0[i] + d.0[i] * count))
+}
+
+fn parse_instructions(lines: impl Iterator<Item = String>, dimensions: usize) -> Result<Vec<Instruction>, String> {
+    let mut result: Vec<Instruction> = vec![];
 
-        let direction = match tokens.get(0).unwrap() {
-            &"U" => Direction::Up,
-            &"D" => Direction::Down,
-            &"L" => Direction::Left,
-            &"R" => Direction::Right,
-            _ => panic!("unsupported instruction"),
-        };
+    for (n, line) in lines.enumerate() {
+        let (direction, count) = line
+            .split_once(' ')
+            .ok_or(format!("line {}: expected a direction and a count", n + 1))?;
 
-        let count = tokens.get(1).unwrap().parse::<u32>().unwrap();
+        let direction = Direction::parse(direction, dimensions).map_err(|e| format!("line {}: {}", n + 1, e))?;
+        let count = count
+            .parse::<u32>()
+            .map_err(|_| format!("line {}: invalid count: {}", n + 1, count))?;
 
         result.push(Instruction {
-            direction: direction,
-            count: count,
+            direction,
+            count,
         })
     }
 
-    result
+    Ok(result)
 }
 
-fn calculate(file: &str) -> u32 {
-    let instructions = load_instructions(file);
+fn load_instructions(file: &str, dimensions: usize) -> Result<Vec<Instruction>, String> {
+    let f = File::open(file).map_err(|e| format!("{}: {}", file, e))?;
 
-    let mut head = Point { x: 0, y: 0 };
-    let mut tail = Point { x: 0, y: 0 };
+    parse_instructions(BufReader::new(f).lines().map(|l| l.unwrap()), dimensions)
+        .map_err(|e| format!("{}: {}", file, e))
+}
 
-    let mut tail_history = vec![head.clone()];
+fn calculate(file: &str) -> u32 {
+    let mut rope: Rope = Rope::new(2, Box::new(Standard));
+    rope.run(&load_instructions(file, 2).unwrap()).unwrap();
 
-    for i in instructions {
-        for _ in 0..i.count {
-            move_leader(&mut head, &i.direction);
-            move_follower(&mut tail, &head);
+    rope.tail().visited.len() as u32
+}
 
-            tail_history.push(tail.clone());
-        }
+fn print_points(points: &[Point]) {
+    for row in Canvas::fitting(points.iter().copied()).text(points, &FxHashSet::default()) {
+        println!("{}", row);
     }
 
-    tail_history.iter().unique().count() as u32
+    println!();
 }
 
-fn print_points(points: &Vec<Point>, size: usize) {
-    let mut grid: Vec<Vec<char>> = vec![vec!['.'; size]; size];
+fn calculate_part2(file: &str, print: bool) -> u32 {
+    let mut rope: Rope = Rope::new(10, Box::new(Standard));
 
-    let mut max_x = size as i32 - 1;
-    let mut max_y = size as i32 - 1;
+    for i in load_instructions(file, 2).unwrap() {
+        for _ in 0..i.count {
+            rope.step(&i.direction).unwrap();
 
-    for (i, point) in points.iter().enumerate() {
-        if point.x > max_x {
-            for row in grid.iter_mut() {
-                row.resize(point.x as usize + 1, '.');
-                max_x = point.x;
+            if print {
+                print_points(&rope.knots);
             }
         }
+    }
 
-        if point.y > max_y {
-            grid.resize(point.y as usize + 1, vec!['.'; max_x as usize + 1]);
-            max_y = point.y;
-        }
-
-        let c = grid
-            .get_mut(point.y as usize)
-            .unwrap()
-            .get_mut(point.x as usize)
-            .unwrap();
+    rope.tail().visited.len() as u32
+}
 
-        if *c == '.' {
-            *c = char::from_digit(i as u32, 10).unwrap();
-        }
-    }
+fn physics<const D: usize>(name: &str) -> Result<Box<dyn physics::Physics<D>>, String> {
+    physics::parse(name).ok_or(format!("unknown physics: {}", name))
+}
 
-    for row in grid.iter().rev() {
-        for c in row {
-            print!("{} ", c);
-        }
+fn simulate<const D: usize>(input: &str, knots: usize, physics_name: &str) -> Result<(), String> {
+    let mut rope = Rope::<D>::new(knots, physics(physics_name)?);
+    rope.run(&load_instructions(input, D)?)?;
 
-        print!("\n");
+    println!("knot\tvisited\tmoves\tbounds");
+    for (i, stats) in rope.stats.iter().enumerate() {
+        println!("{}\t{}\t{}\t{}..{}", i, stats.visited.len(), stats.moves, stats.min, stats.max);
     }
 
-    print!("\n");
+    Ok(())
 }
 
-fn calculate_part2(file: &str, print: bool) -> u32 {
-    let instructions = load_instructions(file);
-
-    let mut points = vec![Point::new(0, 0); 10];
-
-    let mut results = vec![points.last().unwrap().clone()];
+fn run_command(args: &[String]) -> Result<(), String> {
+    let mut args = args.iter();
+    let (input, command) = input_and_command(&mut args)?;
+
+    let mut knots = 10;
+    let mut physics_name = "standard";
+    let mut three_d = false;
+    let mut every = 1;
+    let mut delay = 0.1;
+    let mut scale = 4;
+    let mut output = None;
+
+    while let Some(arg) = args.next() {
+        match (command.as_str(), arg.as_str()) {
+            ("simulate" | "draw" | "animate", "-knots") => {
+                let value = next_value(&mut args, arg)?;
+                knots = value
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|k| *k > 0)
+                    .ok_or(format!("invalid knot count: {}", value))?;
+            }
+            ("simulate" | "draw" | "animate", "-physics") => physics_name = next_value(&mut args, arg)?,
+            ("simulate", "-3d") => three_d = true,
+            ("animate", "-every") => every = parse_number(next_value(&mut args, arg)?)?,
+            ("animate", "-delay") => delay = parse_number(next_value(&mut args, arg)?)?,
+            ("animate", "-scale") => scale = parse_number(next_value(&mut args, arg)?)?,
+            ("animate", a) if !a.starts_with('-') => output = Some(a),
+            (_, a) => return Err(format!("unknown option for {}: {}", command, a)),
+        }
+    }
 
-    for i in instructions {
-        for _ in 0..i.count {
-            move_leader(points.first_mut().unwrap(), &i.direction);
+    match command.as_str() {
+        "simulate" if three_d => simulate::<3>(input, knots, physics_name)?,
+        "simulate" => simulate::<2>(input, knots, physics_name)?,
+        "draw" => {
+            let mut rope: Rope = Rope::new(knots, physics(physics_name)?);
+            rope.run(&load_instructions(input, 2)?)?;
 
-            for i in 1..points.len() {
-                let leader = points.get(i - 1).unwrap().clone();
-                let follower = points.get_mut(i).unwrap();
-                move_follower(follower, &leader);
+            for row in Canvas::for_rope(&rope).text(&rope.knots, &rope.tail().visited) {
+                println!("{}", row);
+            }
+        }
+        "animate" => {
+            let output = output.ok_or("animate needs an output file")?;
+            if every == 0 || scale == 0 {
+                return Err("-every and -scale need to be at least 1".to_string());
             }
 
-            if print {
-                print_points(&points, 6);
+            let instructions = load_instructions(input, 2)?;
+            let animation = Animation::record(&instructions, knots, physics(physics_name)?, every)?;
+            let path = Path::new(output);
+
+            match path.extension().and_then(|e| e.to_str()) {
+                Some("cast") => animation.write_cast(path, delay),
+                Some("gif") => animation.write_gif(path, delay, scale),
+                _ => return Err(format!("{} should end in .cast or .gif", output)),
             }
+            .map_err(|e| format!("{}: {}", output, e))?;
 
-            results.push(points.last().unwrap().clone());
+            println!("{} frames written to {}", animation.len(), output);
         }
+        c => return Err(format!("unknown command: {}", c)),
     }
 
-    results.iter().unique().count() as u32
+    Ok(())
 }
 
 fn main() {
-    println!("result: {}", calculate("input/problem.txt"));
-    println!(
-        "result part2: {}",
-        calculate_part2("input/problem.txt", false)
-    );
+    common::cli::main(USAGE, run_command, || {
+        println!("result: {}", calculate("input/problem.txt"));
+        println!(
+            "result part2: {}",
+            calculate_part2("input/problem.txt", false)
+        );
+    });
 }
 
 #[test]
@@ -197,3 +236,23 @@ fn test_example() {
 fn test_example_part2() {
     assert_eq!(1, calculate_part2("input/example.txt", true));
 }
+
+#[test]
+fn test_diagonal_and_3d_motions() {
+    let lines = ["UL 2", "R 1", "DRF 3"].iter().map(|l| l.to_string());
+    assert_eq!(
+        Err("line 3: unsupported direction: DRF".to_string()),
+        parse_instructions(lines.clone(), 2).map(|_| ())
+    );
+
+    let instructions = parse_instructions(lines, 3).unwrap();
+    assert_eq!(Direction([-1, 1, 0]), instructions[0].direction);
+    assert_eq!(Direction([1, -1, 1]), instructions[2].direction);
+
+    let mut rope = Rope::<3>::new(3, Box::new(Standard));
+    rope.run(&instructions).unwrap();
+    assert_eq!(Point([2, -1, 3]), rope.knots[0]);
+    assert_eq!(Point([1, -1, 1]), rope.knots[2]);
+
+    assert!(Direction::parse("UD", 2).is_err());
+}
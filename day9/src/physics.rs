@@ -0,0 +1,112 @@
+use crate::Point;
+
+// How a knot follows the one in front of it. Given where both are after the
+// leader has moved, returns where the follower ends up, or an error when the
+// two are further apart than the rule can ever make up for. A follower whose
+// leader is still where it was has to be returned unchanged: Rope::step stops
+// at the first knot that doesn't move and leaves the rest of the rope as is.
+pub trait Physics<const D: usize> {
+    fn follow(&self, leader: Point<D>, follower: Point<D>) -> Result<Point<D>, String>;
+}
+
+fn separated<const D: usize>(leader: Point<D>, follower: Point<D>, rule: &str) -> String {
+    format!("{} is too far from {} to follow with {}", follower, leader, rule)
+}
+
+// The puzzle's rope: knots touch when they're adjacent, diagonals included,
+// otherwise the follower moves one step straight or diagonally towards the
+// leader.
+pub struct Standard;
+
+impl<const D: usize> Physics<D> for Standard {
+    fn follow(&self, leader: Point<D>, follower: Point<D>) -> Result<Point<D>, String> {
+        match follower.chebyshev(leader) {
+            0 | 1 => Ok(follower),
+            2 => Ok(follower.step_towards(leader)),
+            _ => Err(separated(leader, follower, "the standard rope")),
+        }
+    }
+}
+
+// Knots only touch side by side and only move along one axis at a time, the
+// one with the biggest gap, the first of them on a tie.
+pub struct FourConnected;
+
+impl<const D: usize> Physics<D> for FourConnected {
+    fn follow(&self, leader: Point<D>, follower: Point<D>) -> Result<Point<D>, String> {
+        let gaps = follower.gaps(leader);
+
+        match follower.manhattan(leader) {
+            0 | 1 => Ok(follower),
+            2 => {
+                let axis = (0..D).rev().max_by_key(|i| gaps[*i].abs()).unwrap();
+                let mut p = follower;
+                p.0[axis] += gaps[axis].signum();
+
+                Ok(p)
+            }
+            _ => Err(separated(leader, follower, "a 4-connected rope")),
+        }
+    }
+}
+
+// A rope with slack between the knots, the follower stays put until the
+// leader is more than length away and then moves like the standard rope.
+pub struct Elastic {
+    pub length: i32,
+}
+
+impl<const D: usize> Physics<D> for Elastic {
+    fn follow(&self, leader: Point<D>, follower: Point<D>) -> Result<Point<D>, String> {
+        let distance = follower.chebyshev(leader);
+
+        if distance <= self.length {
+            Ok(follower)
+        } else if distance == self.length + 1 {
+            Ok(follower.step_towards(leader))
+        } else {
+            Err(separated(leader, follower, &format!("an elastic rope of length {}", self.length)))
+        }
+    }
+}
+
+// standard, 4-connected or elastic:LENGTH
+pub fn parse<const D: usize>(name: &str) -> Option<Box<dyn Physics<D>>> {
+    match name {
+        "standard" => Some(Box::new(Standard)),
+        "4-connected" => Some(Box::new(FourConnected)),
+        _ => {
+            let length = name.strip_prefix("elastic:")?.parse::<i32>().ok()?;
+            (length > 0).then(|| Box::new(Elastic { length }) as Box<dyn Physics<D>>)
+        }
+    }
+}
+
+#[test]
+fn test_standard() {
+    let origin = Point::origin();
+
+    assert_eq!(Ok(origin), Standard.follow(Point([1, -1]), origin));
+    assert_eq!(Ok(Point([0, 1])), Standard.follow(Point([0, 2]), origin));
+    assert_eq!(Ok(Point([-1, 1])), Standard.follow(Point([-2, 1]), origin));
+    assert_eq!(Ok(Point([1, 1])), Standard.follow(Point([2, 2]), origin));
+    assert!(Standard.follow(Point([3, 0]), origin).is_err());
+}
+
+#[test]
+fn test_four_connected_and_elastic() {
+    let origin = Point::origin();
+
+    assert_eq!(Ok(origin), FourConnected.follow(Point([0, -1]), origin));
+    assert_eq!(Ok(Point([1, 0])), FourConnected.follow(Point([1, 1]), origin));
+    assert_eq!(Ok(Point([0, -1])), FourConnected.follow(Point([0, -2]), origin));
+    assert_eq!(
+        Err("(0, 0) is too far from (2, 1) to follow with a 4-connected rope".to_string()),
+        FourConnected.follow(Point([2, 1]), origin)
+    );
+
+    let elastic = Elastic { length: 3 };
+    assert_eq!(Ok(origin), elastic.follow(Point([3, -2]), origin));
+    assert_eq!(Ok(Point([1, -1])), elastic.follow(Point([4, -2]), origin));
+    assert!(elastic.follow(Point([5, 0]), origin).is_err());
+}
@@ -0,0 +1,60 @@
+use std::fmt::Display;
+
+// A position in D dimensions, x then y then z. Ropes are 2D unless asked
+// otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Point<const D: usize = 2>(pub [i32; D]);
+
+impl<const D: usize> Point<D> {
+    pub fn origin() -> Point<D> {
+        Point([0; D])
+    }
+
+    fn zip(self, other: Point<D>, f: impl Fn(i32, i32) -> i32) -> Point<D> {
+        Point(std::array::from_fn(|i| f(self.0[i], other.0[i])))
+    }
+
+    pub fn min(self, other: Point<D>) -> Point<D> {
+        self.zip(other, i32::min)
+    }
+
+    pub fn max(self, other: Point<D>) -> Point<D> {
+        self.zip(other, i32::max)
+    }
+
+    // other - self along each axis
+    pub fn gaps(self, other: Point<D>) -> [i32; D] {
+        self.zip(other, |a, b| b - a).0
+    }
+
+    // one step along every axis other is away on
+    pub fn step_towards(self, other: Point<D>) -> Point<D> {
+        self.zip(other, |a, b| a + (b - a).signum())
+    }
+
+    pub fn chebyshev(self, other: Point<D>) -> i32 {
+        self.gaps(other).iter().map(|g| g.abs()).max().unwrap_or(0)
+    }
+
+    pub fn manhattan(self, other: Point<D>) -> i32 {
+        self.gaps(other).iter().map(|g| g.abs()).sum()
+    }
+}
+
+impl Point<2> {
+    pub fn x(self) -> i32 {
+        self.0[0]
+    }
+
+    pub fn y(self) -> i32 {
+        self.0[1]
+    }
+}
+
+impl<const D: usize> Display for Point<D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let coords = self.0.iter().map(|c| c.to_string()).collect::<Vec<String>>();
+
+        write!(f, "({})", coords.join(", "))
+    }
+}
@@ -0,0 +1,233 @@
+use std::{io, path::Path};
+
+use common::animation::{Cast, Gif};
+use rustc_hash::FxHashSet;
+
+use crate::{physics::Physics, rope::Rope, Direction, Instruction, Point};
+
+#[derive(Clone, Copy, PartialEq)]
+enum Cell {
+    Empty,
+    Start,
+    Trail,
+    Knot(usize),
+}
+
+// H for the head then 1-9 like the puzzle, letters after that and + once
+// those run out too
+pub fn knot_label(i: usize) -> char {
+    const LABELS: &[u8] = b"H123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+    LABELS.get(i).map_or('+', |c| *c as char)
+}
+
+// The area drawn, always including the start so the rope can be seen
+// relative to it. y grows upwards like the motions.
+pub struct Canvas {
+    min: Point,
+    max: Point,
+}
+
+impl Canvas {
+    pub fn fitting(points: impl Iterator<Item = Point>) -> Canvas {
+        let mut canvas = Canvas {
+            min: Point::origin(),
+            max: Point::origin(),
+        };
+
+        for p in points {
+            canvas.min = canvas.min.min(p);
+            canvas.max = canvas.max.max(p);
+        }
+
+        canvas
+    }
+
+    // big enough for everywhere any knot of the rope has been
+    pub fn for_rope(rope: &Rope) -> Canvas {
+        Canvas::fitting(rope.stats.iter().flat_map(|s| [s.min, s.max]))
+    }
+
+    pub fn width(&self) -> usize {
+        (self.max.x() - self.min.x() + 1) as usize
+    }
+
+    pub fn height(&self) -> usize {
+        (self.max.y() - self.min.y() + 1) as usize
+    }
+
+    // row-major from the top row down, anything outside the canvas is left
+    // out and earlier knots are drawn over later ones
+    fn cells(&self, knots: &[Point], trail: &FxHashSet<Point>) -> Vec<Cell> {
+        let mut cells = vec![Cell::Empty; self.width() * self.height()];
+
+        let index = |p: &Point| {
+            let inside = *p == p.max(self.min) && *p == p.min(self.max);
+            inside.then(|| (self.max.y() - p.y()) as usize * self.width() + (p.x() - self.min.x()) as usize)
+        };
+
+        for p in trail {
+            if let Some(i) = index(p) {
+                cells[i] = Cell::Trail;
+            }
+        }
+
+        if let Some(i) = index(&Point::origin()) {
+            cells[i] = Cell::Start;
+        }
+
+        for (k, p) in knots.iter().enumerate().rev() {
+            if let Some(i) = index(p) {
+                cells[i] = Cell::Knot(k);
+            }
+        }
+
+        cells
+    }
+
+    pub fn text(&self, knots: &[Point], trail: &FxHashSet<Point>) -> Vec<String> {
+        let cells = self.cells(knots, trail);
+
+        cells
+            .chunks(self.width())
+            .map(|row| {
+                row.iter()
+                    .map(|c| match c {
+                        Cell::Empty => '.',
+                        Cell::Start => 's',
+                        Cell::Trail => '#',
+                        Cell::Knot(k) => knot_label(*k),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+// The rope after every few steps along with where the tail went in between.
+struct Frame {
+    knots: Vec<Point>,
+    trail: Vec<Point>,
+}
+
+pub struct Animation {
+    frames: Vec<Frame>,
+    canvas: Canvas,
+}
+
+impl Animation {
+    // runs the motions keeping every nth step, and always the last one
+    pub fn record(
+        instructions: &[Instruction],
+        knots: usize,
+        physics: Box<dyn Physics<2>>,
+        every: usize,
+    ) -> Result<Animation, String> {
+        let mut rope: Rope = Rope::new(knots, physics);
+        let mut frames = vec![Frame {
+            knots: rope.knots.clone(),
+            trail: vec![Point::origin()],
+        }];
+
+        let steps = instructions
+            .iter()
+            .flat_map(|i| (0..i.count).map(|_| &i.direction))
+            .collect::<Vec<&Direction>>();
+
+        let mut trail = vec![];
+        for (n, direction) in steps.iter().enumerate() {
+            rope.step(direction).map_err(|e| format!("step {}: {}", n + 1, e))?;
+            trail.push(*rope.knots.last().unwrap());
+
+            if (n + 1) % every == 0 || n + 1 == steps.len() {
+                frames.push(Frame {
+                    knots: rope.knots.clone(),
+                    trail: std::mem::take(&mut trail),
+                });
+            }
+        }
+
+        Ok(Animation {
+            canvas: Canvas::for_rope(&rope),
+            frames,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    // each frame with the tail's trail up to that point
+    fn replay(&self, mut draw: impl FnMut(&[Point], &FxHashSet<Point>) -> io::Result<()>) -> io::Result<()> {
+        let mut trail = FxHashSet::default();
+
+        for frame in self.frames.iter() {
+            trail.extend(frame.trail.iter().copied());
+            draw(&frame.knots, &trail)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn write_cast(&self, path: &Path, delay: f64) -> io::Result<()> {
+        let mut cast = Cast::create(path, self.canvas.width(), self.canvas.height(), delay)?;
+        self.replay(|knots, trail| cast.frame(&self.canvas.text(knots, trail)))?;
+
+        cast.finish()
+    }
+
+    // Draws each cell as a scale x scale square, the head red fading to blue
+    // for the tail.
+    pub fn write_gif(&self, path: &Path, delay: f64, scale: usize) -> io::Result<()> {
+        // background, start, trail then a gradient for the knots
+        let mut palette = vec![16, 16, 24, 110, 110, 110, 40, 70, 110];
+        for i in 0..253 {
+            let t = i as f64 / 252.0;
+            palette.extend([(255.0 * (1.0 - t)) as u8, 60, (255.0 * t) as u8]);
+        }
+
+        let knots = self.frames[0].knots.len();
+        let colour = |c: &Cell| match c {
+            Cell::Empty => 0,
+            Cell::Start => 1,
+            Cell::Trail => 2,
+            Cell::Knot(k) => 3 + (k * 252).checked_div(knots - 1).unwrap_or(0) as u8,
+        };
+
+        let mut gif = Gif::create(path, self.canvas.width(), self.canvas.height(), scale, delay, &palette)?;
+        self.replay(|knots, trail| {
+            let cells = self.canvas.cells(knots, trail);
+            gif.frame(&cells.iter().map(colour).collect::<Vec<u8>>())
+        })
+    }
+}
+
+#[test]
+fn test_negative_coordinates_and_long_ropes() {
+    let knots = (0..12).map(|i| Point([-i, -2 * i])).collect::<Vec<Point>>();
+    let canvas = Canvas::fitting(knots.iter().copied());
+    assert_eq!((12, 23), (canvas.width(), canvas.height()));
+
+    let text = canvas.text(&knots, &FxHashSet::from_iter([Point([-3, 0])]));
+    assert_eq!("........#..H", text[0]);
+    assert_eq!("..........1.", text[2]);
+    assert_eq!("b...........", text[22]);
+}
+
+#[test]
+fn test_cast() {
+    let instructions = crate::load_instructions("input/example.txt", 2).unwrap();
+    let animation = Animation::record(&instructions, 10, Box::new(crate::physics::Standard), 5).unwrap();
+    // the start and every fifth of the 24 steps, then the last one
+    assert_eq!(6, animation.len());
+
+    let path = std::env::temp_dir().join(format!("day9-{}.cast", std::process::id()));
+    animation.write_cast(&path, 0.1).unwrap();
+    let cast = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let lines = cast.lines().collect::<Vec<&str>>();
+    assert_eq!("{\"version\": 2, \"width\": 6, \"height\": 5}", lines[0]);
+    assert_eq!(7, lines.len());
+    assert!(lines[6].starts_with("[0.500, \"o\", \"\\u001b[H"));
+}
@@ -0,0 +1,164 @@
+use rustc_hash::FxHashSet;
+
+use crate::{move_leader, moved, physics::Physics, Direction, Instruction, Point};
+
+// Everything one knot did over a simulation.
+pub struct KnotStats<const D: usize = 2> {
+    pub visited: FxHashSet<Point<D>>,
+    // corners of the smallest box holding every visited position
+    pub min: Point<D>,
+    pub max: Point<D>,
+    // the steps where the knot actually changed position
+    pub moves: u64,
+}
+
+impl<const D: usize> KnotStats<D> {
+    fn new(start: Point<D>) -> KnotStats<D> {
+        KnotStats {
+            visited: FxHashSet::from_iter([start]),
+            min: start,
+            max: start,
+            moves: 0,
+        }
+    }
+
+    fn record(&mut self, p: Point<D>) {
+        self.visited.insert(p);
+        self.min = self.min.min(p);
+        self.max = self.max.max(p);
+        self.moves += 1;
+    }
+}
+
+// A rope of any number of knots, the first being the head.
+pub struct Rope<const D: usize = 2> {
+    pub knots: Vec<Point<D>>,
+    pub stats: Vec<KnotStats<D>>,
+    physics: Box<dyn Physics<D>>,
+}
+
+impl<const D: usize> Rope<D> {
+    pub fn new(knots: usize, physics: Box<dyn Physics<D>>) -> Rope<D> {
+        assert!(knots > 0, "a rope needs at least one knot");
+
+        let start = Point::origin();
+        Rope {
+            knots: vec![start; knots],
+            stats: (0..knots).map(|_| KnotStats::new(start)).collect(),
+            physics,
+        }
+    }
+
+    // Moves the head one step and lets every other knot catch up, a knot that
+    // stays put leaves the rest of the rope behind it where it is too. Returns
+    // whether the whole rope moved as one, every knot by the same step.
+    pub fn step(&mut self, direction: &Direction) -> Result<bool, String> {
+        move_leader(&mut self.knots[0], direction);
+        self.stats[0].record(self.knots[0]);
+
+        let mut straight = true;
+        for i in 1..self.knots.len() {
+            let before = self.knots[i];
+            self.knots[i] = self
+                .physics
+                .follow(self.knots[i - 1], before)
+                .map_err(|e| format!("knot {}: {}", i, e))?;
+
+            if self.knots[i] == before {
+                return Ok(false);
+            }
+            self.stats[i].record(self.knots[i]);
+
+            let mut shifted = before;
+            move_leader(&mut shifted, direction);
+            straight &= shifted == self.knots[i];
+        }
+
+        Ok(straight)
+    }
+
+    // The physics only look at where knots are relative to each other, so once
+    // a step has moved the whole rope as one every later step in the same
+    // direction does too. Each knot goes straight to the end of the run, and
+    // as it's a straight line the ends are all the box needs.
+    fn slide(&mut self, direction: &Direction, count: u32) {
+        let count = count as i32;
+
+        for (knot, stats) in self.knots.iter_mut().zip(self.stats.iter_mut()) {
+            let start = *knot;
+            stats.visited.extend((1..=count).map(|k| moved(start, direction, k)));
+
+            *knot = moved(start, direction, count);
+            stats.min = stats.min.min(*knot);
+            stats.max = stats.max.max(*knot);
+            stats.moves += count as u64;
+        }
+    }
+
+    pub fn run(&mut self, instructions: &[Instruction]) -> Result<(), String> {
+        for (n, i) in instructions.iter().enumerate() {
+            for k in 0..i.count {
+                let straight = self
+                    .step(&i.direction)
+                    .map_err(|e| format!("motion {}: {}", n + 1, e))?;
+
+                if straight {
+                    self.slide(&i.direction, i.count - k - 1);
+                    break;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn tail(&self) -> &KnotStats<D> {
+        self.stats.last().unwrap()
+    }
+}
+
+#[test]
+fn test_knot_stats() {
+    let mut rope: Rope = Rope::new(10, Box::new(crate::physics::Standard));
+    rope.run(&crate::load_instructions("input/example.txt", 2).unwrap()).unwrap();
+
+    let visited = rope.stats.iter().map(|s| s.visited.len()).collect::<Vec<usize>>();
+    assert_eq!(vec![21, 13, 7, 4, 3, 2, 1, 1, 1, 1], visited);
+
+    let head = &rope.stats[0];
+    assert_eq!(24, head.moves);
+    assert_eq!((Point([0, 0]), Point([5, 4])), (head.min, head.max));
+
+    assert_eq!(0, rope.tail().moves);
+}
+
+#[test]
+fn test_slide_matches_stepping() {
+    let lines = ["R 40", "UL 25", "D 3", "L 60", "DR 17", "U 1", "R 2", "U 33"].map(|l| l.to_string());
+    let instructions = crate::parse_instructions(lines.into_iter(), 2).unwrap();
+
+    for physics in ["standard", "4-connected", "elastic:3"] {
+        let mut slid: Rope = Rope::new(6, crate::physics::parse(physics).unwrap());
+        let mut stepped: Rope = Rope::new(6, crate::physics::parse(physics).unwrap());
+
+        let result = slid.run(&instructions);
+        for i in instructions.iter() {
+            for _ in 0..i.count {
+                if stepped.step(&i.direction).is_err() {
+                    break;
+                }
+            }
+        }
+
+        // a 4-connected rope can't follow a diagonal move
+        assert_eq!(physics == "4-connected", result.is_err());
+        if result.is_err() {
+            continue;
+        }
+
+        assert_eq!(stepped.knots, slid.knots);
+        for (a, b) in stepped.stats.iter().zip(slid.stats.iter()) {
+            assert_eq!((&a.visited, a.min, a.max, a.moves), (&b.visited, b.min, b.max, b.moves));
+        }
+    }
+}
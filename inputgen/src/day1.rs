@@ -0,0 +1,18 @@
+use crate::rng::Rng;
+
+// calorie counts for each elf, one per line with a blank line between elves
+pub fn calories(rng: &mut Rng, elves: usize, max_items: usize, max_calories: u64) -> String {
+    let mut output = String::new();
+
+    for elf in 0..elves {
+        if elf > 0 {
+            output.push('\n');
+        }
+
+        for _ in 0..rng.range(1, max_items as i64) {
+            output.push_str(&format!("{}\n", rng.range(1, max_calories as i64)));
+        }
+    }
+
+    output
+}
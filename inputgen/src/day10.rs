@@ -0,0 +1,32 @@
+use crate::rng::Rng;
+
+// A program of noop and addx instructions lasting at least the given number
+// of cycles. X is kept within min_x..=max_x so the sprite stays near the
+// screen.
+pub fn program(rng: &mut Rng, cycles: usize, min_x: i64, max_x: i64) -> String {
+    assert!(min_x <= 1 && 1 <= max_x && min_x < max_x, "X starts at 1 and has to be able to move");
+
+    let mut output = String::new();
+
+    let mut x = 1;
+    let mut elapsed = 0;
+    while elapsed < cycles {
+        if rng.one_in(3) {
+            output.push_str("noop\n");
+            elapsed += 1;
+        } else {
+            let v = loop {
+                let v = rng.range(-20, 20);
+                if v != 0 && (min_x..=max_x).contains(&(x + v)) {
+                    break v;
+                }
+            };
+
+            x += v;
+            output.push_str(&format!("addx {}\n", v));
+            elapsed += 2;
+        }
+    }
+
+    output
+}
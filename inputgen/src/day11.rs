@@ -0,0 +1,45 @@
+use crate::rng::Rng;
+
+const DIVISORS: [u64; 8] = [2, 3, 5, 7, 11, 13, 17, 19];
+
+// Notes for up to eight monkeys. Like the real input every monkey tests
+// against a different small prime and only one squares the worry level, so
+// part 2's arithmetic modulo the product of the tests can't overflow.
+pub fn monkeys(rng: &mut Rng, monkeys: usize, max_items: usize) -> String {
+    assert!((2..=DIVISORS.len()).contains(&monkeys), "there have to be 2 to 8 monkeys");
+
+    let mut divisors = DIVISORS.to_vec();
+    rng.shuffle(&mut divisors);
+
+    let squares = rng.index(monkeys);
+
+    let mut notes = vec![];
+    for (i, divisor) in divisors.iter().take(monkeys).enumerate() {
+        let items = (0..rng.range(1, max_items as i64))
+            .map(|_| rng.range(50, 99).to_string())
+            .collect::<Vec<String>>();
+
+        let operation = if i == squares {
+            "old * old".to_string()
+        } else if rng.one_in(2) {
+            format!("old * {}", rng.range(2, 19))
+        } else {
+            format!("old + {}", rng.range(1, 8))
+        };
+
+        let mut targets = (0..monkeys).filter(|m| *m != i).collect::<Vec<usize>>();
+        rng.shuffle(&mut targets);
+
+        notes.push(format!(
+            "Monkey {}:\n  Starting items: {}\n  Operation: new = {}\n  Test: divisible by {}\n    If true: throw to monkey {}\n    If false: throw to monkey {}\n",
+            i,
+            items.join(", "),
+            operation,
+            divisor,
+            targets[0],
+            targets[targets.len() - 1],
+        ));
+    }
+
+    notes.join("\n")
+}
@@ -0,0 +1,90 @@
+use crate::rng::Rng;
+
+// A heightmap where neighbouring squares never differ by more than one, so
+// every square can be reached from every other one. The first row climbs
+// steadily from a to z, which needs at least 26 columns, so there is always
+// somewhere for S and E to go.
+pub fn heightmap(rng: &mut Rng, width: usize, height: usize) -> String {
+    assert!(width >= 26, "the heightmap needs at least 26 columns to climb from a to z");
+
+    let mut rows: Vec<Vec<i64>> = vec![];
+
+    for _ in 0..height {
+        let mut row: Vec<i64> = vec![];
+
+        for x in 0..width {
+            let h = match rows.last() {
+                None => (x * 25 / (width - 1)) as i64,
+                Some(previous) => {
+                    // stay within one of both the square above and to the left
+                    let above = previous[x];
+                    let (mut min, mut max) = (above - 1, above + 1);
+                    if let Some(left) = row.last() {
+                        min = min.max(left - 1);
+                        max = max.min(left + 1);
+                    }
+
+                    rng.range(min.max(0), max.min(25))
+                }
+            };
+
+            row.push(h);
+        }
+
+        rows.push(row);
+    }
+
+    let cells = |height| {
+        (0..width * rows.len())
+            .filter(|i| rows[i / width][i % width] == height)
+            .collect::<Vec<usize>>()
+    };
+    let start = *rng.pick(&cells(0));
+    let end = *rng.pick(&cells(25));
+
+    let mut output = String::new();
+    for (y, row) in rows.iter().enumerate() {
+        for (x, h) in row.iter().enumerate() {
+            let c = match y * width + x {
+                i if i == start => 'S',
+                i if i == end => 'E',
+                _ => (b'a' + *h as u8) as char,
+            };
+            output.push(c);
+        }
+        output.push('\n');
+    }
+
+    output
+}
+
+#[test]
+fn test_heightmap_is_connected() {
+    let output = heightmap(&mut Rng::new(12), 40, 30);
+    let rows = output
+        .lines()
+        .map(|l| {
+            l.chars()
+                .map(|c| match c {
+                    'S' => 0,
+                    'E' => 25,
+                    c => c as i64 - 'a' as i64,
+                })
+                .collect::<Vec<i64>>()
+        })
+        .collect::<Vec<Vec<i64>>>();
+
+    assert_eq!(1, output.matches('S').count());
+    assert_eq!(1, output.matches('E').count());
+
+    for y in 0..rows.len() {
+        for x in 0..rows[y].len() {
+            if x > 0 {
+                assert!((rows[y][x] - rows[y][x - 1]).abs() <= 1);
+            }
+            if y > 0 {
+                assert!((rows[y][x] - rows[y - 1][x]).abs() <= 1);
+            }
+        }
+    }
+}
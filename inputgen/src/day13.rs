@@ -0,0 +1,32 @@
+use crate::rng::Rng;
+
+pub fn packet(rng: &mut Rng, max_depth: usize, max_length: usize, max_value: u64) -> String {
+    let length = rng.range(0, max_length as i64);
+
+    let items = (0..length)
+        .map(|_| {
+            if max_depth > 0 && rng.one_in(3) {
+                packet(rng, max_depth - 1, max_length, max_value)
+            } else {
+                rng.range(0, max_value as i64).to_string()
+            }
+        })
+        .collect::<Vec<String>>();
+
+    format!("[{}]", items.join(","))
+}
+
+// pairs of packets separated by blank lines
+pub fn packet_pairs(rng: &mut Rng, pairs: usize, max_depth: usize, max_length: usize, max_value: u64) -> String {
+    let pairs = (0..pairs)
+        .map(|_| {
+            format!(
+                "{}\n{}\n",
+                packet(rng, max_depth, max_length, max_value),
+                packet(rng, max_depth, max_length, max_value)
+            )
+        })
+        .collect::<Vec<String>>();
+
+    pairs.join("\n")
+}
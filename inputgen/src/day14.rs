@@ -0,0 +1,45 @@
+use crate::rng::Rng;
+
+// Rock paths made of horizontal and vertical lines, "498,4 -> 498,6 -> 496,6".
+// They stay within spread of the sand source at 500,0 either way and start
+// deeper than spread. A pile of sand reaching the source would need rock under
+// both edges of it, which are always further out than the rock goes, so sand
+// always ends up falling into the abyss in part 1.
+pub fn rock_paths(rng: &mut Rng, paths: usize, max_segments: usize, spread: i64, max_depth: i64) -> String {
+    let min_depth = spread + 2;
+    assert!(min_depth <= max_depth, "the rock has to start deeper than spread");
+    assert!(spread > 0 || min_depth < max_depth, "the rock needs room to go somewhere");
+
+    let mut output = String::new();
+
+    for _ in 0..paths {
+        let mut x = rng.range(500 - spread, 500 + spread);
+        let mut y = rng.range(min_depth, max_depth);
+        let mut points = vec![format!("{},{}", x, y)];
+
+        for _ in 0..rng.range(1, max_segments as i64) {
+            // clamping can leave a segment where it started, those are tried
+            // again so every point is a move from the last
+            loop {
+                let length = rng.range(1, 10);
+
+                let (next_x, next_y) = if rng.one_in(2) {
+                    ((x + rng.pick(&[-length, length])).clamp(500 - spread, 500 + spread), y)
+                } else {
+                    (x, (y + rng.pick(&[-length, length])).clamp(min_depth, max_depth))
+                };
+
+                if (next_x, next_y) != (x, y) {
+                    (x, y) = (next_x, next_y);
+                    break;
+                }
+            }
+
+            points.push(format!("{},{}", x, y));
+        }
+
+        output.push_str(&format!("{}\n", points.join(" -> ")));
+    }
+
+    output
+}
@@ -0,0 +1,12 @@
+use crate::rng::Rng;
+
+// one round of rock paper scissors per line, "A Y"
+pub fn rounds(rng: &mut Rng, rounds: usize) -> String {
+    let mut output = String::new();
+
+    for _ in 0..rounds {
+        output.push_str(&format!("{} {}\n", rng.pick(&['A', 'B', 'C']), rng.pick(&['X', 'Y', 'Z'])));
+    }
+
+    output
+}
@@ -0,0 +1,68 @@
+use crate::rng::Rng;
+
+const ITEMS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+fn fill(rng: &mut Rng, from: &[char], count: usize, required: &[char]) -> Vec<char> {
+    let mut items = required.to_vec();
+    while items.len() < count {
+        items.push(*rng.pick(from));
+    }
+
+    rng.shuffle(&mut items);
+
+    items
+}
+
+// Rucksacks in groups of three. Exactly one item type is in both halves of
+// each rucksack and exactly one, the badge, is in all three of a group, as
+// the puzzle promises. Each rucksack draws from its own third of the other
+// item types so no second type ends up shared by the whole group.
+pub fn rucksacks(rng: &mut Rng, groups: usize, compartment: usize) -> String {
+    assert!(compartment >= 2, "a compartment needs room for the shared item and the badge");
+
+    let mut output = String::new();
+
+    for _ in 0..groups {
+        let mut items = ITEMS.chars().collect::<Vec<char>>();
+        rng.shuffle(&mut items);
+
+        let badge = items[0];
+        for pool in items[1..].chunks(17) {
+            let shared = pool[0];
+            let (left, right) = pool[1..].split_at(8);
+
+            let left = fill(rng, left, compartment, &[shared, badge]);
+            let right = fill(rng, right, compartment, &[shared]);
+
+            output.extend(left.iter().chain(right.iter()));
+            output.push('\n');
+        }
+    }
+
+    output
+}
+
+#[test]
+fn test_rucksacks() {
+    use std::collections::HashSet;
+
+    let output = rucksacks(&mut Rng::new(3), 20, 6);
+    let lines = output.lines().collect::<Vec<&str>>();
+    assert_eq!(60, lines.len());
+
+    for line in lines.iter() {
+        let (left, right) = line.split_at(line.len() / 2);
+        let left = left.chars().collect::<HashSet<char>>();
+        let right = right.chars().collect::<HashSet<char>>();
+        assert_eq!(1, left.intersection(&right).count());
+    }
+
+    for group in lines.chunks(3) {
+        let sets = group
+            .iter()
+            .map(|l| l.chars().collect::<HashSet<char>>())
+            .collect::<Vec<HashSet<char>>>();
+        let common = sets[0].iter().filter(|c| sets[1].contains(c) && sets[2].contains(c));
+        assert_eq!(1, common.count());
+    }
+}
@@ -0,0 +1,19 @@
+use crate::rng::Rng;
+
+// pairs of section assignments, "2-4,6-8"
+pub fn range_pairs(rng: &mut Rng, pairs: usize, max_section: u64) -> String {
+    let mut output = String::new();
+
+    for _ in 0..pairs {
+        let mut ranges = vec![];
+        for _ in 0..2 {
+            let a = rng.range(1, max_section as i64);
+            let b = rng.range(1, max_section as i64);
+            ranges.push(format!("{}-{}", a.min(b), a.max(b)));
+        }
+
+        output.push_str(&format!("{}\n", ranges.join(",")));
+    }
+
+    output
+}
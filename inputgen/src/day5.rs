@@ -0,0 +1,85 @@
+use crate::rng::Rng;
+
+// The starting crate stacks drawn the puzzle's way followed by the moves.
+// Every move only takes crates that are there so both the one at a time and
+// all at once crane can run it. The stack labels only line up for up to nine
+// stacks.
+pub fn stacks(rng: &mut Rng, stacks: usize, max_height: usize, moves: usize) -> String {
+    assert!((1..=9).contains(&stacks), "the drawing only has room for 1 to 9 stacks");
+
+    let heights = (0..stacks)
+        .map(|_| rng.range(0, max_height as i64) as usize)
+        .collect::<Vec<usize>>();
+
+    let mut output = String::new();
+
+    let tallest = *heights.iter().max().unwrap();
+    for level in (0..tallest).rev() {
+        let row = heights
+            .iter()
+            .map(|h| {
+                if *h > level {
+                    format!("[{}]", (b'A' + rng.below(26) as u8) as char)
+                } else {
+                    "   ".to_string()
+                }
+            })
+            .collect::<Vec<String>>();
+
+        output.push_str(&format!("{}\n", row.join(" ")));
+    }
+
+    let labels = (1..=stacks).map(|i| format!(" {} ", i)).collect::<Vec<String>>();
+    output.push_str(&format!("{}\n\n", labels.join(" ")));
+
+    // a single stack has nowhere to move crates to
+    let moves = if stacks > 1 { moves } else { 0 };
+
+    let mut heights = heights;
+    for _ in 0..moves {
+        let non_empty = (0..stacks).filter(|s| heights[*s] > 0).collect::<Vec<usize>>();
+        if non_empty.is_empty() {
+            break;
+        }
+
+        let from = *rng.pick(&non_empty);
+        let mut to = rng.index(stacks - 1);
+        if to >= from {
+            to += 1;
+        }
+
+        let count = rng.range(1, heights[from] as i64) as usize;
+        heights[from] -= count;
+        heights[to] += count;
+
+        output.push_str(&format!("move {} from {} to {}\n", count, from + 1, to + 1));
+    }
+
+    output
+}
+
+#[test]
+fn test_moves_are_possible() {
+    let output = stacks(&mut Rng::new(5), 9, 8, 500);
+    let (drawing, moves) = output.split_once("\n\n").unwrap();
+
+    let mut heights = [0; 9];
+    for line in drawing.lines() {
+        for (i, c) in line.chars().enumerate() {
+            if i % 4 == 1 && c.is_ascii_uppercase() {
+                heights[i / 4] += 1;
+            }
+        }
+    }
+
+    for line in moves.lines() {
+        let tokens = line.split(' ').collect::<Vec<&str>>();
+        let count = tokens[1].parse::<usize>().unwrap();
+        let from = tokens[3].parse::<usize>().unwrap() - 1;
+        let to = tokens[5].parse::<usize>().unwrap() - 1;
+
+        assert!(heights[from] >= count);
+        heights[from] -= count;
+        heights[to] += count;
+    }
+}
@@ -0,0 +1,45 @@
+use crate::rng::Rng;
+
+// A datastream of lowercase letters. Everything before marker only uses three
+// letters so neither kind of marker can appear earlier, the fourteen letters
+// from marker on are all different so both have appeared by marker + 14.
+pub fn datastream(rng: &mut Rng, length: usize, marker: usize) -> String {
+    assert!(marker + 14 <= length, "the marker has to fit in the datastream");
+
+    let mut letters = ('a'..='z').collect::<Vec<char>>();
+    rng.shuffle(&mut letters);
+
+    let mut output = String::new();
+    for i in 0..length {
+        let c = if i < marker {
+            letters[rng.index(3)]
+        } else if i < marker + 14 {
+            letters[i - marker + 3]
+        } else {
+            rng.lowercase()
+        };
+
+        output.push(c);
+    }
+
+    output.push('\n');
+
+    output
+}
+
+#[test]
+fn test_marker() {
+    let output = datastream(&mut Rng::new(9), 4096, 1000);
+    let chars = output.trim().chars().collect::<Vec<char>>();
+
+    let first_marker = |window: usize| {
+        chars
+            .windows(window)
+            .position(|w| (1..w.len()).all(|i| !w[..i].contains(&w[i])))
+            .unwrap()
+            + window
+    };
+
+    assert!(first_marker(4) > 1000);
+    assert!(first_marker(14) > 1000 && first_marker(14) <= 1014);
+}
@@ -0,0 +1,106 @@
+use crate::rng::Rng;
+
+fn name(rng: &mut Rng, extension: bool) -> String {
+    let mut name = (0..rng.range(1, 8)).map(|_| rng.lowercase()).collect::<String>();
+
+    if extension {
+        name.push('.');
+        name.extend((0..3).map(|_| rng.lowercase()));
+    }
+
+    name
+}
+
+struct Directory {
+    files: Vec<(String, u64)>,
+    children: Vec<(String, Directory)>,
+}
+
+impl Directory {
+    fn transcript(&self, output: &mut String) {
+        output.push_str("$ ls\n");
+
+        for (name, _) in self.children.iter() {
+            output.push_str(&format!("dir {}\n", name));
+        }
+        for (name, size) in self.files.iter() {
+            output.push_str(&format!("{} {}\n", size, name));
+        }
+
+        for (name, child) in self.children.iter() {
+            output.push_str(&format!("$ cd {}\n", name));
+            child.transcript(output);
+            output.push_str("$ cd ..\n");
+        }
+    }
+}
+
+fn unique_name(rng: &mut Rng, dir: &Directory, extension: bool) -> String {
+    loop {
+        let name = name(rng, extension);
+
+        let taken = dir.files.iter().any(|(n, _)| *n == name)
+            || dir.children.iter().any(|(n, _)| *n == name);
+        if !taken {
+            return name;
+        }
+    }
+}
+
+fn directory_at<'a>(root: &'a mut Directory, path: &[usize]) -> &'a mut Directory {
+    match path.split_first() {
+        Some((first, rest)) => directory_at(&mut root.children[*first].1, rest),
+        None => root,
+    }
+}
+
+// A terminal transcript for a random tree of directories no deeper than
+// max_depth, walked depth first the way the puzzle input is.
+pub fn transcript(rng: &mut Rng, directories: usize, files: usize, max_depth: usize, max_size: u64) -> String {
+    assert!(max_depth > 0 || directories == 0, "directories need a depth of at least 1");
+
+    let mut root = Directory {
+        files: vec![],
+        children: vec![],
+    };
+
+    // every directory as the list of child indexes leading to it from /
+    let mut paths: Vec<Vec<usize>> = vec![vec![]];
+
+    for _ in 0..directories {
+        let parent = loop {
+            let p = rng.pick(&paths).clone();
+            if p.len() < max_depth {
+                break p;
+            }
+        };
+
+        let dir = directory_at(&mut root, &parent);
+        let name = unique_name(rng, dir, false);
+        dir.children.push((
+            name,
+            Directory {
+                files: vec![],
+                children: vec![],
+            },
+        ));
+
+        let mut path = parent;
+        path.push(dir.children.len() - 1);
+        paths.push(path);
+    }
+
+    for _ in 0..files {
+        let path = rng.pick(&paths).clone();
+        let dir = directory_at(&mut root, &path);
+
+        let extension = rng.one_in(2);
+        let name = unique_name(rng, dir, extension);
+        dir.files.push((name, rng.range(1, max_size as i64) as u64));
+    }
+
+    let mut output = "$ cd /\n".to_string();
+    root.transcript(&mut output);
+
+    output
+}
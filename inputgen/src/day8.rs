@@ -0,0 +1,25 @@
+use crate::rng::Rng;
+
+// a grid of tree heights, single digits when they fit and comma separated
+// when max_height goes past 9
+pub fn forest(rng: &mut Rng, width: usize, height: usize, max_height: u8) -> String {
+    let mut output = String::with_capacity((width + 1) * height);
+
+    for _ in 0..height {
+        for x in 0..width {
+            let h = rng.below(max_height as u64 + 1);
+
+            if max_height <= 9 {
+                output.push((b'0' + h as u8) as char);
+            } else {
+                if x > 0 {
+                    output.push(',');
+                }
+                output.push_str(&h.to_string());
+            }
+        }
+        output.push('\n');
+    }
+
+    output
+}
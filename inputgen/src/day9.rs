@@ -0,0 +1,12 @@
+use crate::rng::Rng;
+
+// rope motions, "R 4"
+pub fn motions(rng: &mut Rng, motions: usize, max_steps: u64) -> String {
+    let mut output = String::new();
+
+    for _ in 0..motions {
+        output.push_str(&format!("{} {}\n", rng.pick(&['U', 'D', 'L', 'R']), rng.range(1, max_steps as i64)));
+    }
+
+    output
+}
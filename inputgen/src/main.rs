@@ -0,0 +1,156 @@
+use std::{collections::HashMap, env, ops::RangeInclusive, process};
+
+use inputgen::*;
+
+const USAGE: &str = "usage: inputgen DAY [-seed N] [-PARAMETER VALUE]...
+
+parameters and their defaults:";
+
+type Parameter = (&'static str, i64, RangeInclusive<i64>);
+
+const ANY: i64 = i64::MAX;
+
+// the parameters each day takes with their defaults, roughly the size of the
+// real puzzle input, and the values the generators can work with
+fn parameters(day: &str) -> Option<&'static [Parameter]> {
+    let p: &'static [Parameter] = match day {
+        "day1" => &[("elves", 250, 0..=ANY), ("items", 15, 1..=ANY), ("calories", 70000, 1..=ANY)],
+        "day2" => &[("rounds", 2500, 0..=ANY)],
+        "day3" => &[("groups", 100, 0..=ANY), ("compartment", 12, 2..=ANY)],
+        "day4" => &[("pairs", 1000, 0..=ANY), ("sections", 99, 1..=ANY)],
+        "day5" => &[("stacks", 9, 1..=9), ("height", 8, 0..=ANY), ("moves", 500, 0..=ANY)],
+        "day6" => &[("length", 4096, 14..=ANY), ("marker", 1000, 0..=ANY)],
+        "day7" => &[
+            ("directories", 180, 0..=ANY),
+            ("files", 300, 0..=ANY),
+            ("depth", 10, 1..=ANY),
+            ("size", 300000, 1..=ANY),
+        ],
+        "day8" => &[("width", 99, 0..=ANY), ("height", 99, 0..=ANY), ("max-height", 9, 0..=255)],
+        "day9" => &[("motions", 2000, 0..=ANY), ("steps", 20, 1..=ANY)],
+        "day10" => &[("cycles", 240, 0..=ANY), ("min-x", -5, -ANY..=1), ("max-x", 40, 1..=ANY)],
+        "day11" => &[("monkeys", 8, 2..=8), ("items", 8, 1..=ANY)],
+        "day12" => &[("width", 159, 26..=ANY), ("height", 41, 1..=ANY)],
+        "day13" => &[("pairs", 150, 0..=ANY), ("depth", 4, 0..=ANY), ("length", 6, 0..=ANY), ("value", 10, 0..=ANY)],
+        "day14" => &[("paths", 150, 0..=ANY), ("segments", 6, 1..=ANY), ("spread", 50, 0..=ANY), ("depth", 170, 2..=ANY)],
+        _ => return None,
+    };
+
+    Some(p)
+}
+
+// the limits between parameters that the ranges can't say
+fn check(day: &str, v: &[i64]) -> Result<(), String> {
+    match day {
+        "day6" if v[1] + 14 > v[0] => Err("the marker has to be at least 14 before the end".to_string()),
+        "day10" if v[1] == v[2] => Err("min-x and max-x can't both be 1".to_string()),
+        "day14" if v[3] < v[2] + 2 => Err("the depth has to be at least spread + 2".to_string()),
+        "day14" if v[3] == v[2] + 2 && v[2] == 0 => {
+            Err("with no spread the depth has to be more than 2 for the rock to go anywhere".to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+fn generate(day: &str, rng: &mut Rng, v: &[i64]) -> String {
+    let u = |i: usize| v[i] as usize;
+
+    match day {
+        "day1" => day1::calories(rng, u(0), u(1), v[2] as u64),
+        "day2" => day2::rounds(rng, u(0)),
+        "day3" => day3::rucksacks(rng, u(0), u(1)),
+        "day4" => day4::range_pairs(rng, u(0), v[1] as u64),
+        "day5" => day5::stacks(rng, u(0), u(1), u(2)),
+        "day6" => day6::datastream(rng, u(0), u(1)),
+        "day7" => day7::transcript(rng, u(0), u(1), u(2), v[3] as u64),
+        "day8" => day8::forest(rng, u(0), u(1), v[2] as u8),
+        "day9" => day9::motions(rng, u(0), v[1] as u64),
+        "day10" => day10::program(rng, u(0), v[1], v[2]),
+        "day11" => day11::monkeys(rng, u(0), u(1)),
+        "day12" => day12::heightmap(rng, u(0), u(1)),
+        "day13" => day13::packet_pairs(rng, u(0), u(1), u(2), v[3] as u64),
+        "day14" => day14::rock_paths(rng, u(0), u(1), v[2], v[3]),
+        _ => unreachable!(),
+    }
+}
+
+fn run(args: &[String]) -> Result<String, String> {
+    let mut args = args.iter();
+
+    let day = args.next().ok_or("missing day")?;
+    let parameters = parameters(day).ok_or(format!("unknown day: {}", day))?;
+
+    let mut seed = 1;
+    let mut values: HashMap<&str, i64> = parameters.iter().map(|(name, default, _)| (*name, *default)).collect();
+
+    while let Some(arg) = args.next() {
+        let name = arg.strip_prefix('-').ok_or(format!("unexpected argument: {}", arg))?;
+        let value = args
+            .next()
+            .ok_or(format!("{} needs a value", arg))?
+            .parse::<i64>()
+            .map_err(|_| format!("{} needs a number", arg))?;
+
+        if name == "seed" {
+            seed = value as u64;
+        } else {
+            let v = values
+                .get_mut(name)
+                .ok_or(format!("unknown parameter for {}: {}", day, arg))?;
+            *v = value;
+        }
+    }
+
+    let values = parameters.iter().map(|(name, _, _)| values[name]).collect::<Vec<i64>>();
+    for ((name, _, range), value) in parameters.iter().zip(values.iter()) {
+        if !range.contains(value) {
+            return Err(match (*range.start(), *range.end()) {
+                (start, end) if start == -ANY => format!("{} can't be more than {}", name, end),
+                (start, ANY) => format!("{} can't be less than {}", name, start),
+                (start, end) => format!("{} has to be from {} to {}", name, start, end),
+            });
+        }
+    }
+    check(day, &values)?;
+
+    Ok(generate(day, &mut Rng::new(seed), &values))
+}
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<String>>();
+
+    match run(&args) {
+        Ok(output) => print!("{}", output),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            eprintln!("{}", USAGE);
+            for day in (1..=14).map(|d| format!("day{}", d)) {
+                let p = parameters(&day).unwrap();
+                let p = p.iter().map(|(n, v, _)| format!("-{} {}", n, v)).collect::<Vec<String>>();
+                eprintln!("  {:<6} {}", day, p.join(" "));
+            }
+            process::exit(1);
+        }
+    }
+}
+
+#[test]
+fn test_every_day() {
+    for day in (1..=14).map(|d| format!("day{}", d)) {
+        let args = [day.clone(), "-seed".to_string(), "42".to_string()];
+
+        let first = run(&args).unwrap();
+        assert!(!first.is_empty(), "{}", day);
+        assert_eq!(first, run(&args).unwrap(), "{} isn't deterministic", day);
+    }
+
+    let run = |args: &[&str]| run(&args.iter().map(|a| a.to_string()).collect::<Vec<String>>());
+    assert_eq!(Err("items can't be less than 1".to_string()), run(&["day1", "-items", "0"]));
+    assert_eq!(Err("height can't be less than 1".to_string()), run(&["day12", "-height", "0"]));
+    assert_eq!(Err("max-height has to be from 0 to 255".to_string()), run(&["day8", "-max-height", "300"]));
+    assert_eq!(Err("depth can't be less than 1".to_string()), run(&["day7", "-depth", "0"]));
+    assert_eq!(Err("max-x can't be less than 1".to_string()), run(&["day10", "-max-x", "-3"]));
+    assert_eq!(Err("min-x can't be more than 1".to_string()), run(&["day10", "-min-x", "3"]));
+    assert!(run(&["day14", "-spread", "0", "-depth", "2"]).is_err());
+    assert!(run(&["day14", "-spread", "0", "-depth", "3", "-segments", "40"]).is_ok());
+}
@@ -0,0 +1,88 @@
+// A small splitmix64 generator. The output for a given seed never changes,
+// which a crate like rand doesn't promise across versions, so generated inputs
+// and the answers recorded for them stay valid.
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    // a value in 0..n
+    pub fn below(&mut self, n: u64) -> u64 {
+        assert!(n > 0, "empty range");
+
+        // reject the top of the range so every value is equally likely
+        let zone = u64::MAX - u64::MAX % n;
+        loop {
+            let v = self.next_u64();
+            if v < zone {
+                return v % n;
+            }
+        }
+    }
+
+    // a value in min..=max
+    pub fn range(&mut self, min: i64, max: i64) -> i64 {
+        assert!(min <= max, "empty range");
+
+        min + self.below((max - min) as u64 + 1) as i64
+    }
+
+    pub fn index(&mut self, len: usize) -> usize {
+        self.below(len as u64) as usize
+    }
+
+    // true one time in n
+    pub fn one_in(&mut self, n: u64) -> bool {
+        self.below(n) == 0
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.index(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.index(i + 1);
+            items.swap(i, j);
+        }
+    }
+
+    pub fn lowercase(&mut self) -> char {
+        (b'a' + self.below(26) as u8) as char
+    }
+}
+
+#[test]
+fn test_deterministic() {
+    let mut a = Rng::new(7);
+    let mut b = Rng::new(7);
+
+    let first = (0..100).map(|_| a.next_u64()).collect::<Vec<u64>>();
+    assert_eq!(first, (0..100).map(|_| b.next_u64()).collect::<Vec<u64>>());
+
+    // pinned so an accidental change to the generator shows up here
+    assert_eq!(0xe220a8397b1dcdaf, Rng::new(0).next_u64());
+}
+
+#[test]
+fn test_range() {
+    let mut rng = Rng::new(1);
+
+    for _ in 0..1000 {
+        let v = rng.range(-3, 3);
+        assert!((-3..=3).contains(&v));
+    }
+}